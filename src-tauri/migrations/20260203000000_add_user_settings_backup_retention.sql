@@ -0,0 +1,2 @@
+ALTER TABLE user_settings
+ADD COLUMN backup_retention INTEGER NOT NULL DEFAULT 7;
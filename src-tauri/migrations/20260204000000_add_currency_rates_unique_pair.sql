@@ -0,0 +1,21 @@
+-- Keep one rate per pair and month: manual rates win over provider rates, then the latest timestamp
+DELETE FROM currency_rates
+WHERE id IN (
+    SELECT id
+    FROM (
+        SELECT
+            id,
+            ROW_NUMBER() OVER (
+                PARTITION BY from_currency, to_currency, year, month
+                ORDER BY
+                    CASE WHEN provider = 'manual' THEN 0 ELSE 1 END,
+                    timestamp DESC,
+                    id DESC
+            ) AS precedence
+        FROM currency_rates
+    )
+    WHERE precedence > 1
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_currency_rates_pair_period
+ON currency_rates (from_currency, to_currency, year, month);
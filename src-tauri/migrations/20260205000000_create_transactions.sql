@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS transactions (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    date DATE NOT NULL,
+    -- Change to the account balance as recorded in entries (positive increases it)
+    amount REAL NOT NULL,
+    payee TEXT,
+    category TEXT,
+    memo TEXT,
+    -- The other leg of a transfer between two accounts
+    transfer_id TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (transfer_id) REFERENCES transactions(id) ON DELETE SET NULL DEFERRABLE INITIALLY DEFERRED
+);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_account_date ON transactions (account_id, date);
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS budget_categories (
+    id TEXT PRIMARY KEY NOT NULL,
+    -- Matched case-insensitively against transactions.category
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    -- Whether unspent money carries into the next month
+    rollover BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS budgets (
+    id TEXT PRIMARY KEY NOT NULL,
+    category_id TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+    -- Amounts are in the user's home currency
+    amount REAL NOT NULL,
+    -- Unspent amount carried over from the previous month
+    rollover_amount REAL NOT NULL DEFAULT 0,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (category_id) REFERENCES budget_categories(id) ON DELETE CASCADE,
+    UNIQUE (category_id, year, month)
+);
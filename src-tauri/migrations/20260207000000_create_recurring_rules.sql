@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS recurring_rules (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL UNIQUE,
+    -- 'fixed_amount', 'fixed_delta', 'percentage_growth' or 'amortization'
+    rule_type TEXT NOT NULL,
+    -- fixed_amount: the balance; fixed_delta: change per month; amortization: monthly payment
+    amount REAL NOT NULL DEFAULT 0,
+    -- Annual rate as a decimal (0.05 = 5%) for percentage_growth and amortization
+    rate REAL NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
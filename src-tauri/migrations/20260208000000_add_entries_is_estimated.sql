@@ -0,0 +1,2 @@
+-- Carried-forward or otherwise unconfirmed balances, cleared once the user confirms them
+ALTER TABLE entries ADD COLUMN is_estimated BOOLEAN NOT NULL DEFAULT 0;
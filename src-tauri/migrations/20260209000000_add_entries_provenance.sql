@@ -0,0 +1,9 @@
+-- 'confirmed', 'estimated', 'imported' or 'carried_forward'
+ALTER TABLE entries ADD COLUMN status TEXT NOT NULL DEFAULT 'confirmed';
+-- Where the value came from, e.g. 'csv_import', 'recurring_rule', 'carry_forward'
+ALTER TABLE entries ADD COLUMN source TEXT;
+ALTER TABLE entries ADD COLUMN note TEXT;
+
+UPDATE entries SET status = 'carried_forward', source = 'carry_forward' WHERE is_estimated = 1;
+
+ALTER TABLE entries DROP COLUMN is_estimated;
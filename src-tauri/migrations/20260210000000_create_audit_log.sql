@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS audit_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    -- 'entry', 'account', 'currency_rate' or 'retirement_plan'
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    -- 'insert', 'update' or 'delete'
+    action TEXT NOT NULL,
+    -- JSON snapshots of the row before and after the change
+    old_value TEXT,
+    new_value TEXT,
+    -- The command that made the change, when known
+    origin TEXT,
+    -- Business key of entries, so history survives an entry being deleted and re-created
+    account_id TEXT,
+    year INTEGER,
+    month INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log (entity_type, entity_id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_account_period ON audit_log (account_id, year, month);
@@ -0,0 +1,4 @@
+-- Deleting moves a row to the trash by setting deleted_at; purging removes it for good
+ALTER TABLE accounts ADD COLUMN deleted_at DATETIME;
+ALTER TABLE balance_sheets ADD COLUMN deleted_at DATETIME;
+ALTER TABLE retirement_plans ADD COLUMN deleted_at DATETIME;
@@ -0,0 +1,14 @@
+-- Keep only the most recently updated entry for each sheet, account and month
+DELETE FROM entries
+WHERE rowid NOT IN (
+    SELECT rowid FROM (
+        SELECT rowid, ROW_NUMBER() OVER (
+            PARTITION BY balance_sheet_id, account_id, month
+            ORDER BY updated_at DESC, rowid DESC
+        ) AS position
+        FROM entries
+    )
+    WHERE position = 1
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_entries_sheet_account_month ON entries (balance_sheet_id, account_id, month);
@@ -0,0 +1,22 @@
+-- Money put into (or taken out of) an account in a month, in the account's currency.
+-- Takes precedence over the account's transactions for that month when both exist.
+CREATE TABLE IF NOT EXISTS contributions (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+    amount REAL NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    UNIQUE (account_id, year, month)
+);
+
+CREATE TABLE IF NOT EXISTS monthly_income (
+    id TEXT PRIMARY KEY NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+    -- In the user's home currency
+    amount REAL NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (year, month)
+);
@@ -1,17 +1,38 @@
+use crate::error::AppError;
 use crate::models::{
-    Account, BalanceSheet, CurrencyRate, Entry, OnboardingStep, RetirementPlan,
-    RetirementPlanProjection, UserSettings,
+    Account, AuditLogEntry, BalanceSheet, Budget, BudgetCategory, Contribution, CurrencyRate,
+    Entry, MonthlyIncome, OnboardingStep, RecurringRule, RetirementPlan, RetirementPlanProjection,
+    Transaction, UserSettings,
 };
 use crate::services::account::AccountService;
-use crate::services::balance_sheet::BalanceSheetService;
-use crate::services::currency_rates::currency_rate::CurrencyRateService;
+use crate::services::archive::{
+    ArchiveImportReport, ArchiveService, ConflictMode, DatabaseArchive,
+};
+use crate::services::audit::{with_origin, AuditService};
+use crate::services::backup::{
+    BackupInfo, BackupService, BACKUP_REASON_MANUAL, BACKUP_REASON_PRE_RESTORE,
+};
+use crate::services::balance_sheet::{BalanceSheetService, CarryForwardOptions};
+use crate::services::budget::{BudgetReport, BudgetService};
+use crate::services::currency_rates::currency_rate::{CurrencyRateService, MANUAL_PROVIDER};
+use crate::services::currency_rates::rate_import::{RateImportReport, RateImportService};
 use crate::services::currency_rates::sync::SyncService;
-use crate::services::entry::EntryService;
-use crate::services::net_worth::{NetWorthDataPoint, NetWorthService};
+use crate::services::entry::{DraftEntry, EntryCell, EntryProvenance, EntryService};
+use crate::services::entry_import::{EntryImportReport, EntryImportService};
+use crate::services::growth::{GrowthReport, GrowthService};
+use crate::services::net_worth::{
+    NetWorthBreakdown, NetWorthDataPoint, NetWorthQuery, NetWorthService, UnconvertibleMonth,
+};
 use crate::services::onboarding::OnboardingService;
+use crate::services::recurring_rule::{RecurringRuleInput, RecurringRuleService};
 use crate::services::retirement::{RetirementProjection, RetirementService, WITHDRAWAL_RATE_HIGH};
 use crate::services::retirement_plan::RetirementPlanService;
 use crate::services::retirement_plan_projection::RetirementPlanProjectionService;
+use crate::services::savings::{SavingsMonth, SavingsService};
+use crate::services::transaction::{
+    ReconciliationDiscrepancy, TransactionInput, TransactionService, TransferInput,
+};
+use crate::services::trash::{Trash, TrashService};
 use crate::services::user_settings::UserSettingsService;
 use crate::AppState;
 use chrono::NaiveDate;
@@ -21,7 +42,9 @@ use tauri::State;
 // --- User Settings ---
 
 #[tauri::command]
-pub async fn get_user_settings(state: State<'_, AppState>) -> Result<Option<UserSettings>, String> {
+pub async fn get_user_settings(
+    state: State<'_, AppState>,
+) -> Result<Option<UserSettings>, AppError> {
     // We can use get_all and return the first one, or get_by_id if we knew it.
     // Or keep get_all logic. Since UserSettings is a singleton concept, let's just get all and take first.
     let settings = UserSettingsService::get_all(&state.db).await?;
@@ -31,34 +54,68 @@ pub async fn get_user_settings(state: State<'_, AppState>) -> Result<Option<User
 #[tauri::command]
 pub async fn get_net_worth_history(
     state: State<'_, AppState>,
-) -> Result<Vec<NetWorthDataPoint>, String> {
-    NetWorthService::get_history(&state.db).await
+    query: Option<NetWorthQuery>,
+) -> Result<Vec<NetWorthDataPoint>, AppError> {
+    NetWorthService::query_history(&state.db, &query.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_net_worth_breakdown(
+    state: State<'_, AppState>,
+    query: Option<NetWorthQuery>,
+) -> Result<Vec<NetWorthBreakdown>, AppError> {
+    NetWorthService::get_breakdown(&state.db, &query.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_growth_report(
+    state: State<'_, AppState>,
+    query: Option<NetWorthQuery>,
+) -> Result<GrowthReport, AppError> {
+    GrowthService::get_report(&state.db, &query.unwrap_or_default()).await
 }
 
 #[tauri::command]
 pub async fn get_latest_net_worth(
     state: State<'_, AppState>,
-) -> Result<Option<NetWorthDataPoint>, String> {
+) -> Result<Option<NetWorthDataPoint>, AppError> {
     NetWorthService::get_latest(&state.db).await
 }
 
+#[tauri::command]
+pub async fn get_unconvertible_months(
+    state: State<'_, AppState>,
+) -> Result<Vec<UnconvertibleMonth>, AppError> {
+    NetWorthService::get_unconvertible_months(&state.db).await
+}
+
 #[tauri::command]
 pub async fn update_user_settings(
     state: State<'_, AppState>,
     name: String,
     home_currency: String,
     theme: String,
-) -> Result<UserSettings, String> {
+) -> Result<UserSettings, AppError> {
     let pool = &state.db;
-    UserSettingsService::upsert(pool, name, home_currency, theme)
-        .await
-        .map_err(|e| e.to_string())
+    let settings = UserSettingsService::upsert(pool, name, home_currency, theme).await?;
+
+    if settings.needs_exchange_sync {
+        // Trigger background sync for the (possibly new) home currency
+        let pool = state.db.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = SyncService::sync_exchange_rates(&pool).await {
+                eprintln!("Failed to sync rates after updating settings: {e}");
+            }
+        });
+    }
+
+    Ok(settings)
 }
 
 async fn mark_exchange_sync_needed_if_foreign(
     pool: &SqlitePool,
     currency: &str,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let settings_list = UserSettingsService::get_all(pool).await?;
     let settings = match settings_list.first() {
         Some(settings) => settings,
@@ -78,7 +135,7 @@ async fn mark_exchange_sync_needed_if_foreign(
 pub async fn get_all_accounts(
     state: State<'_, AppState>,
     include_archived: bool,
-) -> Result<Vec<Account>, String> {
+) -> Result<Vec<Account>, AppError> {
     AccountService::get_all(&state.db, include_archived).await
 }
 
@@ -86,8 +143,12 @@ pub async fn get_all_accounts(
 pub async fn toggle_archive_account(
     state: State<'_, AppState>,
     id: String,
-) -> Result<Account, String> {
-    AccountService::toggle_archive(&state.db, id).await
+) -> Result<Account, AppError> {
+    with_origin(
+        "toggle_archive_account",
+        AccountService::toggle_archive(&state.db, id),
+    )
+    .await
 }
 
 #[tauri::command]
@@ -97,14 +158,17 @@ pub async fn create_account(
     account_type: String,
     currency: String,
     sub_category: Option<String>,
-) -> Result<Account, String> {
-    let account = AccountService::upsert(
-        &state.db,
-        None,
-        name,
-        account_type,
-        currency.clone(),
-        sub_category,
+) -> Result<Account, AppError> {
+    let account = with_origin(
+        "create_account",
+        AccountService::upsert(
+            &state.db,
+            None,
+            name,
+            account_type,
+            currency.clone(),
+            sub_category,
+        ),
     )
     .await?;
     mark_exchange_sync_needed_if_foreign(&state.db, &currency).await?;
@@ -119,14 +183,17 @@ pub async fn update_account(
     account_type: String,
     currency: String,
     sub_category: Option<String>,
-) -> Result<Account, String> {
-    let account = AccountService::upsert(
-        &state.db,
-        Some(id),
-        name,
-        account_type,
-        currency.clone(),
-        sub_category,
+) -> Result<Account, AppError> {
+    let account = with_origin(
+        "update_account",
+        AccountService::upsert(
+            &state.db,
+            Some(id),
+            name,
+            account_type,
+            currency.clone(),
+            sub_category,
+        ),
     )
     .await?;
     mark_exchange_sync_needed_if_foreign(&state.db, &currency).await?;
@@ -137,19 +204,23 @@ pub async fn update_account(
 pub async fn update_account_order(
     state: State<'_, AppState>,
     ids: Vec<String>,
-) -> Result<(), String> {
-    AccountService::update_order(&state.db, ids).await
+) -> Result<(), AppError> {
+    with_origin(
+        "update_account_order",
+        AccountService::update_order(&state.db, ids),
+    )
+    .await
 }
 
 #[tauri::command]
-pub async fn delete_account(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    AccountService::delete(&state.db, id).await
+pub async fn delete_account(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    with_origin("delete_account", AccountService::delete(&state.db, id)).await
 }
 
 // --- Balance Sheets ---
 
 #[tauri::command]
-pub async fn get_balance_sheets(state: State<'_, AppState>) -> Result<Vec<BalanceSheet>, String> {
+pub async fn get_balance_sheets(state: State<'_, AppState>) -> Result<Vec<BalanceSheet>, AppError> {
     BalanceSheetService::get_all(&state.db).await
 }
 
@@ -157,8 +228,18 @@ pub async fn get_balance_sheets(state: State<'_, AppState>) -> Result<Vec<Balanc
 pub async fn create_balance_sheet(
     state: State<'_, AppState>,
     year: i32,
-) -> Result<BalanceSheet, String> {
-    let sheet = BalanceSheetService::upsert(&state.db, None, year).await?;
+    carry_forward: Option<CarryForwardOptions>,
+) -> Result<BalanceSheet, AppError> {
+    let sheet = match carry_forward {
+        Some(options) => {
+            with_origin(
+                "create_balance_sheet",
+                BalanceSheetService::create_with_carry_forward(&state.db, year, options),
+            )
+            .await?
+        }
+        None => BalanceSheetService::upsert(&state.db, None, year).await?,
+    };
 
     // Trigger background sync
     let pool = state.db.clone();
@@ -172,8 +253,12 @@ pub async fn create_balance_sheet(
 }
 
 #[tauri::command]
-pub async fn delete_balance_sheet(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    BalanceSheetService::delete(&state.db, id).await
+pub async fn delete_balance_sheet(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    with_origin(
+        "delete_balance_sheet",
+        BalanceSheetService::delete(&state.db, id),
+    )
+    .await
 }
 
 // --- Entries ---
@@ -182,8 +267,9 @@ pub async fn delete_balance_sheet(state: State<'_, AppState>, id: String) -> Res
 pub async fn get_entries(
     state: State<'_, AppState>,
     balance_sheet_id: String,
-) -> Result<Vec<Entry>, String> {
-    EntryService::get_by_balance_sheet(&state.db, balance_sheet_id).await
+    status: Option<String>,
+) -> Result<Vec<Entry>, AppError> {
+    EntryService::get_by_balance_sheet(&state.db, balance_sheet_id, status).await
 }
 
 #[tauri::command]
@@ -193,25 +279,373 @@ pub async fn upsert_entry(
     account_id: String,
     month: i32,
     amount: f64,
-) -> Result<Entry, String> {
-    EntryService::upsert(&state.db, balance_sheet_id, account_id, month, amount).await
+    provenance: Option<EntryProvenance>,
+) -> Result<Entry, AppError> {
+    with_origin(
+        "upsert_entry",
+        EntryService::upsert(
+            &state.db,
+            balance_sheet_id,
+            account_id,
+            month,
+            amount,
+            provenance.unwrap_or_default(),
+        ),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn upsert_entries(
+    state: State<'_, AppState>,
+    balance_sheet_id: String,
+    cells: Vec<EntryCell>,
+) -> Result<Vec<Entry>, AppError> {
+    with_origin(
+        "upsert_entries",
+        EntryService::upsert_many(&state.db, balance_sheet_id, cells),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_entry(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    with_origin("delete_entry", EntryService::delete(&state.db, id)).await
+}
+
+#[tauri::command]
+pub async fn clear_month(
+    state: State<'_, AppState>,
+    balance_sheet_id: String,
+    month: u32,
+) -> Result<u64, AppError> {
+    with_origin(
+        "clear_month",
+        EntryService::clear_month(&state.db, balance_sheet_id, month),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn clear_account_year(
+    state: State<'_, AppState>,
+    balance_sheet_id: String,
+    account_id: String,
+) -> Result<u64, AppError> {
+    with_origin(
+        "clear_account_year",
+        EntryService::clear_account_year(&state.db, balance_sheet_id, account_id),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn import_entries_csv(
+    state: State<'_, AppState>,
+    contents: String,
+    dry_run: bool,
+) -> Result<EntryImportReport, AppError> {
+    let report = with_origin(
+        "import_entries_csv",
+        EntryImportService::import_csv(&state.db, &contents, dry_run),
+    )
+    .await?;
+
+    if !dry_run && !report.created_balance_sheet_years.is_empty() {
+        // Trigger background sync for the newly created years
+        let pool = state.db.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = SyncService::sync_exchange_rates(&pool).await {
+                eprintln!("Failed to sync rates after importing entries: {e}");
+            }
+        });
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn confirm_entries(
+    state: State<'_, AppState>,
+    balance_sheet_id: String,
+    month: Option<u32>,
+) -> Result<u64, AppError> {
+    with_origin(
+        "confirm_entries",
+        EntryService::confirm_estimated(&state.db, balance_sheet_id, month),
+    )
+    .await
+}
+
+// --- Change History ---
+
+#[tauri::command]
+pub async fn get_entry_history(
+    state: State<'_, AppState>,
+    account_id: String,
+    year: i32,
+    month: u32,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+    AuditService::get_entry_history(&state.db, account_id, year, month).await
+}
+
+#[tauri::command]
+pub async fn get_audit_history(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: String,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+    AuditService::get_history(&state.db, entity_type, entity_id).await
+}
+
+#[tauri::command]
+pub async fn revert_entry(
+    state: State<'_, AppState>,
+    audit_id: String,
+) -> Result<Option<Entry>, AppError> {
+    with_origin(
+        "revert_entry",
+        AuditService::revert_entry(&state.db, audit_id),
+    )
+    .await
+}
+
+// --- Recurring Rules ---
+
+#[tauri::command]
+pub async fn get_recurring_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<RecurringRule>, AppError> {
+    RecurringRuleService::get_all(&state.db).await
+}
+
+#[tauri::command]
+pub async fn upsert_recurring_rule(
+    state: State<'_, AppState>,
+    id: Option<String>,
+    rule: RecurringRuleInput,
+) -> Result<RecurringRule, AppError> {
+    RecurringRuleService::upsert(&state.db, id, rule).await
+}
+
+#[tauri::command]
+pub async fn delete_recurring_rule(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    RecurringRuleService::delete(&state.db, id).await
+}
+
+#[tauri::command]
+pub async fn preview_recurring_entries(
+    state: State<'_, AppState>,
+    balance_sheet_id: String,
+    month: Option<u32>,
+) -> Result<Vec<DraftEntry>, AppError> {
+    EntryService::preview_recurring(&state.db, &balance_sheet_id, month).await
+}
+
+#[tauri::command]
+pub async fn apply_recurring_entries(
+    state: State<'_, AppState>,
+    balance_sheet_id: String,
+    month: Option<u32>,
+    overwrite: bool,
+) -> Result<Vec<Entry>, AppError> {
+    with_origin(
+        "apply_recurring_entries",
+        EntryService::apply_recurring(&state.db, balance_sheet_id, month, overwrite),
+    )
+    .await
+}
+
+// --- Transactions ---
+
+#[tauri::command]
+pub async fn get_transactions(
+    state: State<'_, AppState>,
+    account_id: Option<String>,
+) -> Result<Vec<Transaction>, AppError> {
+    TransactionService::get_all(&state.db, account_id).await
+}
+
+#[tauri::command]
+pub async fn upsert_transaction(
+    state: State<'_, AppState>,
+    id: Option<String>,
+    transaction: TransactionInput,
+) -> Result<Transaction, AppError> {
+    TransactionService::upsert(&state.db, id, transaction).await
+}
+
+#[tauri::command]
+pub async fn create_transfer(
+    state: State<'_, AppState>,
+    transfer: TransferInput,
+) -> Result<Vec<Transaction>, AppError> {
+    TransactionService::create_transfer(&state.db, transfer).await
+}
+
+#[tauri::command]
+pub async fn delete_transaction(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    TransactionService::delete(&state.db, id).await
+}
+
+#[tauri::command]
+pub async fn reconcile_transactions(
+    state: State<'_, AppState>,
+    year: i32,
+) -> Result<Vec<ReconciliationDiscrepancy>, AppError> {
+    TransactionService::reconcile(&state.db, year).await
+}
+
+// --- Budgets ---
+
+#[tauri::command]
+pub async fn get_budget_categories(
+    state: State<'_, AppState>,
+) -> Result<Vec<BudgetCategory>, AppError> {
+    BudgetService::get_categories(&state.db).await
+}
+
+#[tauri::command]
+pub async fn upsert_budget_category(
+    state: State<'_, AppState>,
+    id: Option<String>,
+    name: String,
+    rollover: bool,
+) -> Result<BudgetCategory, AppError> {
+    BudgetService::upsert_category(&state.db, id, name, rollover).await
+}
+
+#[tauri::command]
+pub async fn delete_budget_category(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), AppError> {
+    BudgetService::delete_category(&state.db, id).await
+}
+
+#[tauri::command]
+pub async fn get_budgets(
+    state: State<'_, AppState>,
+    year: i32,
+    month: u32,
+) -> Result<Vec<Budget>, AppError> {
+    BudgetService::get_budgets(&state.db, year, month).await
+}
+
+#[tauri::command]
+pub async fn set_budget(
+    state: State<'_, AppState>,
+    category_id: String,
+    year: i32,
+    month: u32,
+    amount: f64,
+) -> Result<Budget, AppError> {
+    BudgetService::set_budget(&state.db, category_id, year, month, amount).await
+}
+
+#[tauri::command]
+pub async fn rollover_budgets(
+    state: State<'_, AppState>,
+    year: i32,
+    month: u32,
+) -> Result<Vec<Budget>, AppError> {
+    BudgetService::rollover(&state.db, year, month).await
+}
+
+#[tauri::command]
+pub async fn get_budget_report(
+    state: State<'_, AppState>,
+    year: i32,
+    month: u32,
+) -> Result<BudgetReport, AppError> {
+    BudgetService::get_report(&state.db, year, month).await
+}
+
+// --- Savings ---
+
+#[tauri::command]
+pub async fn get_contributions(
+    state: State<'_, AppState>,
+    year: i32,
+) -> Result<Vec<Contribution>, AppError> {
+    SavingsService::get_contributions(&state.db, year).await
+}
+
+#[tauri::command]
+pub async fn set_contribution(
+    state: State<'_, AppState>,
+    account_id: String,
+    year: i32,
+    month: u32,
+    amount: f64,
+) -> Result<Contribution, AppError> {
+    SavingsService::set_contribution(&state.db, account_id, year, month, amount).await
+}
+
+#[tauri::command]
+pub async fn delete_contribution(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    SavingsService::delete_contribution(&state.db, id).await
+}
+
+#[tauri::command]
+pub async fn get_income(
+    state: State<'_, AppState>,
+    year: i32,
+) -> Result<Vec<MonthlyIncome>, AppError> {
+    SavingsService::get_income(&state.db, year).await
+}
+
+#[tauri::command]
+pub async fn set_income(
+    state: State<'_, AppState>,
+    year: i32,
+    month: u32,
+    amount: f64,
+) -> Result<MonthlyIncome, AppError> {
+    SavingsService::set_income(&state.db, year, month, amount).await
+}
+
+#[tauri::command]
+pub async fn delete_income(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    SavingsService::delete_income(&state.db, id).await
+}
+
+#[tauri::command]
+pub async fn get_savings_report(
+    state: State<'_, AppState>,
+    query: Option<NetWorthQuery>,
+) -> Result<Vec<SavingsMonth>, AppError> {
+    SavingsService::get_report(&state.db, &query.unwrap_or_default()).await
 }
 
 // --- Currency Rates ---
 
 #[tauri::command]
-pub async fn get_currency_rates(state: State<'_, AppState>) -> Result<Vec<CurrencyRate>, String> {
+pub async fn get_currency_rates(state: State<'_, AppState>) -> Result<Vec<CurrencyRate>, AppError> {
     CurrencyRateService::get_all(&state.db).await
 }
 
 #[tauri::command]
-pub async fn sync_exchange_rates(state: State<'_, AppState>) -> Result<UserSettings, String> {
+pub async fn sync_exchange_rates(state: State<'_, AppState>) -> Result<UserSettings, AppError> {
     let _ = SyncService::sync_exchange_rates(&state.db).await?;
     let settings = UserSettingsService::get_all(&state.db).await?;
     settings
         .into_iter()
         .next()
-        .ok_or_else(|| "User settings not found".to_string())
+        .ok_or_else(|| AppError::missing("User settings"))
+}
+
+#[tauri::command]
+pub async fn import_currency_rates_csv(
+    state: State<'_, AppState>,
+    contents: String,
+    dry_run: bool,
+) -> Result<RateImportReport, AppError> {
+    with_origin(
+        "import_currency_rates_csv",
+        RateImportService::import_csv(&state.db, &contents, dry_run),
+    )
+    .await
 }
 
 #[tauri::command]
@@ -224,24 +658,147 @@ pub async fn upsert_currency_rate(
     rate: f64,
     month: u32,
     year: i32,
-) -> Result<CurrencyRate, String> {
-    let provider = provider.unwrap_or_else(|| "manual".to_string());
-    CurrencyRateService::upsert(
-        &state.db,
-        id,
-        from_currency,
-        to_currency,
-        provider,
-        rate,
-        month,
-        year,
+) -> Result<CurrencyRate, AppError> {
+    let provider = provider.unwrap_or_else(|| MANUAL_PROVIDER.to_string());
+    with_origin(
+        "upsert_currency_rate",
+        CurrencyRateService::upsert(
+            &state.db,
+            id,
+            from_currency,
+            to_currency,
+            provider,
+            rate,
+            month,
+            year,
+        ),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_currency_rate(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    with_origin(
+        "delete_currency_rate",
+        CurrencyRateService::delete(&state.db, id),
     )
     .await
 }
 
+// --- Trash ---
+
+#[tauri::command]
+pub async fn get_trash(state: State<'_, AppState>) -> Result<Trash, AppError> {
+    TrashService::get_all(&state.db).await
+}
+
 #[tauri::command]
-pub async fn delete_currency_rate(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    CurrencyRateService::delete(&state.db, id).await
+pub async fn restore_account(state: State<'_, AppState>, id: String) -> Result<Account, AppError> {
+    with_origin("restore_account", AccountService::restore(&state.db, id)).await
+}
+
+#[tauri::command]
+pub async fn purge_account(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    with_origin("purge_account", AccountService::purge(&state.db, id)).await
+}
+
+#[tauri::command]
+pub async fn restore_balance_sheet(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<BalanceSheet, AppError> {
+    with_origin(
+        "restore_balance_sheet",
+        BalanceSheetService::restore(&state.db, id),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn purge_balance_sheet(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    with_origin(
+        "purge_balance_sheet",
+        BalanceSheetService::purge(&state.db, id),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn restore_retirement_plan(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<RetirementPlan, AppError> {
+    with_origin(
+        "restore_retirement_plan",
+        RetirementPlanService::restore(&state.db, id),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn purge_retirement_plan(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    with_origin(
+        "purge_retirement_plan",
+        RetirementPlanService::purge(&state.db, id),
+    )
+    .await
+}
+
+// --- Archive ---
+
+#[tauri::command]
+pub async fn export_all(state: State<'_, AppState>) -> Result<DatabaseArchive, AppError> {
+    ArchiveService::export_all(&state.db).await
+}
+
+#[tauri::command]
+pub async fn import_all(
+    state: State<'_, AppState>,
+    archive: DatabaseArchive,
+    mode: ConflictMode,
+) -> Result<ArchiveImportReport, AppError> {
+    ArchiveService::import_all(&state.db, archive, mode).await
+}
+
+// --- Backups ---
+
+#[tauri::command]
+pub async fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupInfo>, AppError> {
+    BackupService::list_backups(&state.backup_dir)
+}
+
+#[tauri::command]
+pub async fn create_backup(state: State<'_, AppState>) -> Result<BackupInfo, AppError> {
+    let backup =
+        BackupService::create_backup(&state.db, &state.backup_dir, BACKUP_REASON_MANUAL).await?;
+    let retention = BackupService::get_retention(&state.db).await;
+    BackupService::prune_backups(&state.backup_dir, retention)?;
+    Ok(backup)
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    file_name: String,
+) -> Result<(), AppError> {
+    // Keep the current state around in case the restore was a mistake
+    BackupService::create_backup(&state.db, &state.backup_dir, BACKUP_REASON_PRE_RESTORE).await?;
+    BackupService::stage_restore(&state.backup_dir, &file_name, &state.db_path)?;
+
+    // The staged backup is swapped in on startup, before the database is opened
+    state.db.close().await;
+    app.restart();
+}
+
+#[tauri::command]
+pub async fn update_backup_retention(
+    state: State<'_, AppState>,
+    backup_retention: i64,
+) -> Result<UserSettings, AppError> {
+    let settings = UserSettingsService::set_backup_retention(&state.db, backup_retention).await?;
+    BackupService::prune_backups(&state.backup_dir, backup_retention)?;
+    Ok(settings)
 }
 
 // --- Onboarding ---
@@ -249,7 +806,7 @@ pub async fn delete_currency_rate(state: State<'_, AppState>, id: String) -> Res
 #[tauri::command]
 pub async fn get_onboarding_status(
     state: State<'_, AppState>,
-) -> Result<Vec<OnboardingStep>, String> {
+) -> Result<Vec<OnboardingStep>, AppError> {
     OnboardingService::get_status(&state.db).await
 }
 
@@ -257,7 +814,7 @@ pub async fn get_onboarding_status(
 pub async fn complete_onboarding_step(
     state: State<'_, AppState>,
     step_key: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     OnboardingService::complete_step(&state.db, step_key).await
 }
 
@@ -273,12 +830,16 @@ pub async fn create_retirement_plan(
     expected_monthly_expenses: f64,
     return_scenario: String,
     inflation_rate: f64,
-) -> Result<RetirementPlan, String> {
+) -> Result<RetirementPlan, AppError> {
     let annual_return_rate = RetirementService::annual_return_rate(&return_scenario)?;
 
     let retirement_date = match target_retirement_year {
-        Some(year) => NaiveDate::from_ymd_opt(year, 1, 1)
-            .ok_or_else(|| format!("Invalid retirement year: {year}"))?,
+        Some(year) => NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| {
+            AppError::validation(
+                "targetRetirementYear",
+                format!("Invalid retirement year: {year}"),
+            )
+        })?,
         None => {
             let years = RetirementService::years_to_retirement_with_inflation(
                 starting_net_worth,
@@ -301,23 +862,26 @@ pub async fn create_retirement_plan(
         retirement_date,
     );
 
-    let mut tx = state.db.begin().await.map_err(|e| e.to_string())?;
-
-    let plan = RetirementPlanService::create_with_executor(
-        &mut *tx,
-        name,
-        target_retirement_year,
-        starting_net_worth,
-        monthly_contribution,
-        expected_monthly_expenses,
-        return_scenario,
-        inflation_rate,
+    let mut tx = state.db.begin().await?;
+
+    let plan = with_origin(
+        "create_retirement_plan",
+        RetirementPlanService::create_in_tx(
+            &mut tx,
+            name,
+            target_retirement_year,
+            starting_net_worth,
+            monthly_contribution,
+            expected_monthly_expenses,
+            return_scenario,
+            inflation_rate,
+        ),
     )
     .await?;
 
     RetirementPlanProjectionService::save_projections_in_tx(&mut tx, &plan.id, data_points).await?;
 
-    tx.commit().await.map_err(|e| e.to_string())?;
+    tx.commit().await?;
 
     Ok(plan)
 }
@@ -325,7 +889,7 @@ pub async fn create_retirement_plan(
 #[tauri::command]
 pub async fn get_retirement_plans(
     state: State<'_, AppState>,
-) -> Result<Vec<RetirementPlan>, String> {
+) -> Result<Vec<RetirementPlan>, AppError> {
     RetirementPlanService::get_all(&state.db).await
 }
 
@@ -333,7 +897,7 @@ pub async fn get_retirement_plans(
 pub async fn get_retirement_plan(
     state: State<'_, AppState>,
     id: String,
-) -> Result<Option<RetirementPlan>, String> {
+) -> Result<Option<RetirementPlan>, AppError> {
     RetirementPlanService::get_by_id(&state.db, id).await
 }
 
@@ -348,17 +912,20 @@ pub async fn update_retirement_plan(
     expected_monthly_expenses: f64,
     return_scenario: String,
     inflation_rate: f64,
-) -> Result<RetirementPlan, String> {
-    let plan = RetirementPlanService::update(
-        &state.db,
-        id,
-        name,
-        target_retirement_date,
-        starting_net_worth,
-        monthly_contribution,
-        expected_monthly_expenses,
-        return_scenario.clone(),
-        inflation_rate,
+) -> Result<RetirementPlan, AppError> {
+    let plan = with_origin(
+        "update_retirement_plan",
+        RetirementPlanService::update(
+            &state.db,
+            id,
+            name,
+            target_retirement_date,
+            starting_net_worth,
+            monthly_contribution,
+            expected_monthly_expenses,
+            return_scenario.clone(),
+            inflation_rate,
+        ),
     )
     .await?;
 
@@ -394,8 +961,15 @@ pub async fn update_retirement_plan(
 }
 
 #[tauri::command]
-pub async fn delete_retirement_plan(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    RetirementPlanService::delete(&state.db, id).await
+pub async fn delete_retirement_plan(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), AppError> {
+    with_origin(
+        "delete_retirement_plan",
+        RetirementPlanService::delete(&state.db, id),
+    )
+    .await
 }
 
 #[tauri::command]
@@ -406,7 +980,7 @@ pub async fn calculate_retirement_projection(
     return_scenario: String,
     target_retirement_year: Option<i32>,
     inflation_rate: Option<f64>,
-) -> Result<RetirementProjection, String> {
+) -> Result<RetirementProjection, AppError> {
     RetirementService::calculate_projection(
         starting_net_worth,
         monthly_contribution,
@@ -421,6 +995,6 @@ pub async fn calculate_retirement_projection(
 pub async fn get_retirement_plan_projections(
     state: State<'_, AppState>,
     plan_id: String,
-) -> Result<Vec<RetirementPlanProjection>, String> {
+) -> Result<Vec<RetirementPlanProjection>, AppError> {
     RetirementPlanProjectionService::get_by_plan_id(&state.db, &plan_id).await
 }
@@ -0,0 +1,192 @@
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::fmt;
+
+/**
+ * Error returned by every service and command.
+ * Serialized to the frontend as `{ code, message, ...fields }` so callers can branch
+ * on `code` (e.g. "CONFLICT") instead of string-matching the message.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    NotFound {
+        entity: String,
+        id: Option<String>,
+    },
+    Conflict {
+        entity: String,
+        message: String,
+    },
+    Validation {
+        field: Option<String>,
+        message: String,
+    },
+    Database(String),
+    Network(String),
+    ProviderUnsupported {
+        provider: String,
+        currency: String,
+    },
+    Io(String),
+}
+
+impl AppError {
+    pub fn not_found(entity: &str, id: impl Into<String>) -> Self {
+        Self::NotFound {
+            entity: entity.to_string(),
+            id: Some(id.into()),
+        }
+    }
+
+    // For singletons and lookups that aren't keyed by id (e.g. user settings)
+    pub fn missing(entity: &str) -> Self {
+        Self::NotFound {
+            entity: entity.to_string(),
+            id: None,
+        }
+    }
+
+    pub fn conflict(entity: &str, message: impl Into<String>) -> Self {
+        Self::Conflict {
+            entity: entity.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn validation(field: &str, message: impl Into<String>) -> Self {
+        Self::Validation {
+            field: Some(field.to_string()),
+            message: message.into(),
+        }
+    }
+
+    // Validation failure that isn't tied to a single input field
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::Validation {
+            field: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound { .. } => "NOT_FOUND",
+            Self::Conflict { .. } => "CONFLICT",
+            Self::Validation { .. } => "VALIDATION",
+            Self::Database(_) => "DATABASE",
+            Self::Network(_) => "NETWORK",
+            Self::ProviderUnsupported { .. } => "PROVIDER_UNSUPPORTED",
+            Self::Io(_) => "IO",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound {
+                entity,
+                id: Some(id),
+            } => write!(f, "{entity} with ID {id} not found"),
+            Self::NotFound { entity, id: None } => write!(f, "{entity} not found"),
+            Self::Conflict { message, .. } | Self::Validation { message, .. } => {
+                write!(f, "{message}")
+            }
+            Self::Database(message) => write!(f, "Database error: {message}"),
+            Self::Network(message) => write!(f, "Network error: {message}"),
+            Self::ProviderUnsupported { provider, currency } => {
+                write!(
+                    f,
+                    "Provider {provider} does not support currency {currency}"
+                )
+            }
+            Self::Io(message) => write!(f, "File system error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            Self::NotFound { entity, id } => {
+                state.serialize_field("entity", entity)?;
+                state.serialize_field("id", id)?;
+            }
+            Self::Conflict { entity, .. } => {
+                state.serialize_field("entity", entity)?;
+            }
+            Self::Validation { field, .. } => {
+                state.serialize_field("field", field)?;
+            }
+            Self::ProviderUnsupported { provider, currency } => {
+                state.serialize_field("provider", provider)?;
+                state.serialize_field("currency", currency)?;
+            }
+            Self::Database(_) | Self::Network(_) | Self::Io(_) => {}
+        }
+        state.end()
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => Self::missing("Record"),
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                Self::conflict("Record", db_err.message())
+            }
+            sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => {
+                Self::invalid(db_err.message())
+            }
+            _ => Self::Database(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Network(e.to_string())
+    }
+}
+
+impl From<csv::Error> for AppError {
+    fn from(e: csv::Error) -> Self {
+        Self::invalid(format!("Invalid CSV: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppError;
+
+    #[test]
+    fn app_error_serializes_with_code_message_and_fields() {
+        let value = serde_json::to_value(AppError::not_found("Account", "acc-1"))
+            .expect("serialize not found");
+        assert_eq!(value["code"], "NOT_FOUND");
+        assert_eq!(value["message"], "Account with ID acc-1 not found");
+        assert_eq!(value["entity"], "Account");
+        assert_eq!(value["id"], "acc-1");
+
+        let value = serde_json::to_value(AppError::validation("month", "Invalid month: 13"))
+            .expect("serialize validation");
+        assert_eq!(value["code"], "VALIDATION");
+        assert_eq!(value["field"], "month");
+
+        let value = serde_json::to_value(AppError::Database("disk I/O error".to_string()))
+            .expect("serialize database");
+        assert_eq!(value["code"], "DATABASE");
+        assert_eq!(value["message"], "Database error: disk I/O error");
+    }
+}
@@ -1,18 +1,26 @@
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 use std::fs;
+use std::path::PathBuf;
 use tauri::Manager;
 
+use crate::services::backup::{BackupService, BACKUP_DIR_NAME};
 use crate::services::currency_rates::sync::SyncService;
 
 mod commands;
+mod error;
 mod models;
 mod services;
 
 // Create a custom struct to hold the database pool
 struct AppState {
     db: SqlitePool,
+    db_path: PathBuf,
+    backup_dir: PathBuf,
 }
 
+// How often the background task checks whether a scheduled backup is due
+const BACKUP_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 // # Panics
 //
@@ -35,6 +43,10 @@ pub fn run() {
             // Path for the SQLite database
             let db_path = app_data_dir.join("oink.db");
             let db_url = format!("sqlite://{}?mode=rwc", db_path.to_string_lossy());
+            let backup_dir = app_data_dir.join(BACKUP_DIR_NAME);
+
+            // Swap in a backup staged by `restore_backup` before anything opens the database
+            BackupService::apply_pending_restore(&db_path).expect("failed to restore backup");
 
             tauri::async_runtime::block_on(async move {
                 // Connect to the database
@@ -44,6 +56,14 @@ pub fn run() {
                     .await
                     .expect("failed to connect to database");
 
+                // Snapshot existing data before applying new migrations
+                let retention = BackupService::get_retention(&pool).await;
+                if let Err(e) =
+                    BackupService::create_pre_migration_backup(&pool, &backup_dir, retention).await
+                {
+                    eprintln!("Failed to back up database before migrations: {e}");
+                }
+
                 // Run migrations
                 sqlx::migrate!("./migrations")
                     .run(&pool)
@@ -58,8 +78,32 @@ pub fn run() {
                     }
                 });
 
+                // Rolling backups (background)
+                let pool_clone = pool.clone();
+                let backup_dir_clone = backup_dir.clone();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        if let Err(e) = BackupService::create_scheduled_backup_if_due(
+                            &pool_clone,
+                            &backup_dir_clone,
+                        )
+                        .await
+                        {
+                            eprintln!("Failed to create scheduled backup: {e}");
+                        }
+                        tokio::time::sleep(std::time::Duration::from_secs(
+                            BACKUP_CHECK_INTERVAL_SECS,
+                        ))
+                        .await;
+                    }
+                });
+
                 // Manage the pool in Tauri state
-                app.manage(AppState { db: pool });
+                app.manage(AppState {
+                    db: pool,
+                    db_path,
+                    backup_dir,
+                });
             });
 
             Ok(())
@@ -78,12 +122,62 @@ pub fn run() {
             commands::delete_balance_sheet,
             commands::get_entries,
             commands::upsert_entry,
+            commands::upsert_entries,
+            commands::delete_entry,
+            commands::clear_month,
+            commands::clear_account_year,
+            commands::import_entries_csv,
+            commands::confirm_entries,
+            commands::get_entry_history,
+            commands::get_audit_history,
+            commands::revert_entry,
+            commands::get_recurring_rules,
+            commands::upsert_recurring_rule,
+            commands::delete_recurring_rule,
+            commands::preview_recurring_entries,
+            commands::apply_recurring_entries,
+            commands::get_transactions,
+            commands::upsert_transaction,
+            commands::create_transfer,
+            commands::delete_transaction,
+            commands::reconcile_transactions,
+            commands::get_budget_categories,
+            commands::upsert_budget_category,
+            commands::delete_budget_category,
+            commands::get_budgets,
+            commands::set_budget,
+            commands::rollover_budgets,
+            commands::get_budget_report,
+            commands::get_contributions,
+            commands::set_contribution,
+            commands::delete_contribution,
+            commands::get_income,
+            commands::set_income,
+            commands::delete_income,
+            commands::get_savings_report,
             commands::get_currency_rates,
             commands::sync_exchange_rates,
+            commands::import_currency_rates_csv,
             commands::upsert_currency_rate,
             commands::delete_currency_rate,
             commands::get_net_worth_history,
+            commands::get_net_worth_breakdown,
+            commands::get_growth_report,
             commands::get_latest_net_worth,
+            commands::get_unconvertible_months,
+            commands::get_trash,
+            commands::restore_account,
+            commands::purge_account,
+            commands::restore_balance_sheet,
+            commands::purge_balance_sheet,
+            commands::restore_retirement_plan,
+            commands::purge_retirement_plan,
+            commands::export_all,
+            commands::import_all,
+            commands::list_backups,
+            commands::create_backup,
+            commands::restore_backup,
+            commands::update_backup_retention,
             commands::get_onboarding_status,
             commands::complete_onboarding_step,
             commands::create_retirement_plan,
@@ -2,6 +2,14 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+fn default_backup_retention() -> i64 {
+    crate::services::backup::DEFAULT_BACKUP_RETENTION
+}
+
+fn default_entry_status() -> String {
+    crate::services::entry::ENTRY_STATUS_CONFIRMED.to_string()
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct UserSettings {
@@ -10,6 +18,8 @@ pub struct UserSettings {
     pub home_currency: String,
     pub theme: String,
     pub needs_exchange_sync: bool,
+    #[serde(default = "default_backup_retention")]
+    pub backup_retention: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -25,6 +35,9 @@ pub struct Account {
     pub sort_order: i32,
     pub is_archived: bool,
     pub created_at: DateTime<Utc>,
+    // Set while the account is in the trash
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -33,9 +46,12 @@ pub struct BalanceSheet {
     pub id: String,
     pub year: i32,
     pub created_at: DateTime<Utc>,
+    // Set while the sheet is in the trash
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct Entry {
     pub id: String,
@@ -44,6 +60,82 @@ pub struct Entry {
     pub month: u32,
     pub amount: f64,
     pub updated_at: DateTime<Utc>,
+    // 'confirmed', 'estimated', 'imported' or 'carried_forward'
+    #[serde(default = "default_entry_status")]
+    pub status: String,
+    pub source: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringRule {
+    pub id: String,
+    pub account_id: String,
+    pub rule_type: String, // 'fixed_amount', 'fixed_delta', 'percentage_growth' or 'amortization'
+    pub amount: f64,
+    pub rate: f64,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub id: String,
+    pub account_id: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub payee: Option<String>,
+    pub category: Option<String>,
+    pub memo: Option<String>,
+    pub transfer_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetCategory {
+    pub id: String,
+    pub name: String,
+    pub rollover: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Budget {
+    pub id: String,
+    pub category_id: String,
+    pub year: i32,
+    pub month: u32,
+    pub amount: f64,
+    pub rollover_amount: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Contribution {
+    pub id: String,
+    pub account_id: String,
+    pub year: i32,
+    pub month: u32,
+    // New money from outside the tracked accounts, in the account's currency; negative for withdrawals
+    pub amount: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyIncome {
+    pub id: String,
+    pub year: i32,
+    pub month: u32,
+    pub amount: f64, // In the user's home currency
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -80,6 +172,9 @@ pub struct RetirementPlan {
     pub inflation_rate: f64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    // Set while the plan is in the trash
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -93,6 +188,23 @@ pub struct RetirementPlanProjection {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub action: String, // 'insert', 'update' or 'delete'
+    // JSON snapshots of the row
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub origin: Option<String>,
+    pub account_id: Option<String>,
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::{RetirementPlan, RetirementPlanProjection};
@@ -111,6 +223,7 @@ mod tests {
             inflation_rate: 2.5,
             created_at: Utc.with_ymd_and_hms(2026, 1, 22, 0, 0, 0).unwrap(),
             updated_at: Utc.with_ymd_and_hms(2026, 1, 22, 0, 0, 0).unwrap(),
+            deleted_at: None,
         };
 
         let value = serde_json::to_value(plan).expect("serialize retirement plan");
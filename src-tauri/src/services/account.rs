@@ -1,5 +1,7 @@
-use crate::models::Account;
-use sqlx::SqlitePool;
+use crate::error::AppError;
+use crate::models::{Account, Entry};
+use crate::services::audit::{AuditService, AUDIT_ENTITY_ACCOUNT};
+use sqlx::{Executor, Sqlite, SqlitePool};
 use uuid::Uuid;
 
 pub struct AccountService;
@@ -9,34 +11,48 @@ impl AccountService {
     pub async fn get_all(
         pool: &SqlitePool,
         include_archived: bool,
-    ) -> Result<Vec<Account>, String> {
+    ) -> Result<Vec<Account>, AppError> {
         let query = if include_archived {
-            "SELECT * FROM accounts ORDER BY sort_order ASC"
+            "SELECT * FROM accounts WHERE deleted_at IS NULL ORDER BY sort_order ASC"
         } else {
-            "SELECT * FROM accounts WHERE is_archived = 0 ORDER BY sort_order ASC"
+            "SELECT * FROM accounts WHERE is_archived = 0 AND deleted_at IS NULL ORDER BY sort_order ASC"
         };
 
         sqlx::query_as::<_, Account>(query)
             .fetch_all(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
+    }
+
+    // LIST (trash)
+    pub async fn get_trashed(pool: &SqlitePool) -> Result<Vec<Account>, AppError> {
+        sqlx::query_as::<_, Account>(
+            "SELECT * FROM accounts WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
     }
 
     // READ
-    pub async fn get_by_id(pool: &SqlitePool, id: String) -> Result<Option<Account>, String> {
-        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+    pub async fn get_by_id(pool: &SqlitePool, id: String) -> Result<Option<Account>, AppError> {
+        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ? AND deleted_at IS NULL")
             .bind(id)
             .fetch_optional(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
     }
 
-    pub async fn get_by_name(pool: &SqlitePool, name: String) -> Result<Option<Account>, String> {
+    // Includes accounts in the trash, since their names are still taken
+    pub async fn get_by_name<'e, E>(executor: E, name: String) -> Result<Option<Account>, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE name = ?")
             .bind(name)
-            .fetch_optional(pool)
+            .fetch_optional(executor)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
     }
 
     // UPSERT
@@ -47,20 +63,31 @@ impl AccountService {
         account_type: String,
         currency: String,
         sub_category: Option<String>,
-    ) -> Result<Account, String> {
+    ) -> Result<Account, AppError> {
         let now = chrono::Utc::now();
 
         // Check for unique name
         if let Some(existing) = Self::get_by_name(pool, name.clone()).await? {
             if id.as_ref() != Some(&existing.id) {
-                return Err(format!("Account with name '{name}' already exists"));
+                let message = if existing.deleted_at.is_some() {
+                    format!("Account with name '{name}' is in the trash. Restore or purge it first")
+                } else {
+                    format!("Account with name '{name}' already exists")
+                };
+                return Err(AppError::conflict("Account", message));
             }
         }
 
+        let exists = match &id {
+            Some(uid) => Self::get_by_id(pool, uid.clone()).await?,
+            None => None,
+        };
+
+        let mut tx = pool.begin().await?;
+
         if let Some(uid) = id {
-            let exists: Option<Account> = Self::get_by_id(pool, uid.clone()).await?;
-            if exists.is_some() {
-                return sqlx::query_as::<_, Account>(
+            if let Some(existing) = exists {
+                let account = sqlx::query_as::<_, Account>(
                     "UPDATE accounts SET name = ?, account_type = ?, currency = ?, sub_category = ? WHERE id = ? RETURNING *"
                 )
                 .bind(name)
@@ -68,9 +95,18 @@ impl AccountService {
                 .bind(currency)
                 .bind(&sub_category)
                 .bind(uid)
-                .fetch_one(pool)
-                .await
-                .map_err(|e| e.to_string());
+                .fetch_one(&mut *tx)
+                .await?;
+                AuditService::record(
+                    &mut tx,
+                    AUDIT_ENTITY_ACCOUNT,
+                    &account.id,
+                    Some(&existing),
+                    Some(&account),
+                )
+                .await?;
+                tx.commit().await?;
+                return Ok(account);
             }
         }
 
@@ -78,12 +114,11 @@ impl AccountService {
 
         // Get current max sort_order
         let max_order: (i32,) = sqlx::query_as("SELECT COALESCE(MAX(sort_order), 0) FROM accounts")
-            .fetch_one(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .fetch_one(&mut *tx)
+            .await?;
         let next_order = max_order.0 + 1;
 
-        sqlx::query_as::<_, Account>(
+        let account = sqlx::query_as::<_, Account>(
             "INSERT INTO accounts (id, name, account_type, currency, sub_category, sort_order, created_at) VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING *"
         )
         .bind(new_id)
@@ -93,49 +128,165 @@ impl AccountService {
         .bind(sub_category)
         .bind(next_order)
         .bind(now)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| e.to_string())
+        .fetch_one(&mut *tx)
+        .await?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_ACCOUNT,
+            &account.id,
+            None,
+            Some(&account),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(account)
     }
 
-    pub async fn update_order(pool: &SqlitePool, ids: Vec<String>) -> Result<(), String> {
-        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    pub async fn update_order(pool: &SqlitePool, ids: Vec<String>) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
 
         for (index, id) in ids.into_iter().enumerate() {
             let order = (index + 1) as i32;
-            sqlx::query("UPDATE accounts SET sort_order = ? WHERE id = ?")
-                .bind(order)
-                .bind(id)
-                .execute(&mut *tx)
-                .await
-                .map_err(|e| e.to_string())?;
+            let existing = sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = ?")
+                .bind(&id)
+                .fetch_optional(&mut *tx)
+                .await?;
+            let updated = sqlx::query_as::<_, Account>(
+                "UPDATE accounts SET sort_order = ? WHERE id = ? RETURNING *",
+            )
+            .bind(order)
+            .bind(&id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            if let Some(account) = updated {
+                AuditService::record(
+                    &mut tx,
+                    AUDIT_ENTITY_ACCOUNT,
+                    &id,
+                    existing.as_ref(),
+                    Some(&account),
+                )
+                .await?;
+            }
         }
 
-        tx.commit().await.map_err(|e| e.to_string())
+        tx.commit().await.map_err(AppError::from)
     }
 
-    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), String> {
-        sqlx::query("DELETE FROM accounts WHERE id = ?")
-            .bind(id)
-            .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+    // DELETE (moves the account to the trash; its entries stay put until it's purged)
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let existing = sqlx::query_as::<_, Account>(
+            "SELECT * FROM accounts WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let trashed = sqlx::query_as::<_, Account>(
+            "UPDATE accounts SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL RETURNING *",
+        )
+        .bind(chrono::Utc::now())
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_ACCOUNT,
+            &id,
+            existing.as_ref(),
+            trashed.as_ref(),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // RESTORE (from the trash)
+    pub async fn restore(pool: &SqlitePool, id: String) -> Result<Account, AppError> {
+        let mut tx = pool.begin().await?;
+        let trashed = sqlx::query_as::<_, Account>(
+            "SELECT * FROM accounts WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::not_found("Account", id.clone()))?;
+        let restored = sqlx::query_as::<_, Account>(
+            "UPDATE accounts SET deleted_at = NULL WHERE id = ? RETURNING *",
+        )
+        .bind(&id)
+        .fetch_one(&mut *tx)
+        .await?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_ACCOUNT,
+            &id,
+            Some(&trashed),
+            Some(&restored),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(restored)
+    }
+
+    // PURGE (permanently deletes a trashed account; entries cascade, so they're logged as deleted too)
+    pub async fn purge(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let in_trash: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM accounts WHERE id = ? AND deleted_at IS NOT NULL")
+                .bind(&id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if in_trash.is_none() {
+            return Err(AppError::not_found("Account", id));
+        }
+
+        let entries = sqlx::query_as::<_, Entry>("SELECT * FROM entries WHERE account_id = ?")
+            .bind(&id)
+            .fetch_all(&mut *tx)
+            .await?;
+        for entry in &entries {
+            AuditService::record_entry(&mut tx, Some(entry), None).await?;
+        }
+
+        let existing =
+            sqlx::query_as::<_, Account>("DELETE FROM accounts WHERE id = ? RETURNING *")
+                .bind(&id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        AuditService::record(&mut tx, AUDIT_ENTITY_ACCOUNT, &id, existing.as_ref(), None).await?;
+
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn toggle_archive(pool: &SqlitePool, id: String) -> Result<Account, String> {
+    pub async fn toggle_archive(pool: &SqlitePool, id: String) -> Result<Account, AppError> {
         let account = Self::get_by_id(pool, id.clone())
             .await?
-            .ok_or_else(|| format!("Account with ID {id} not found"))?;
+            .ok_or_else(|| AppError::not_found("Account", id.clone()))?;
 
         let new_archived_state = !account.is_archived;
 
-        sqlx::query_as::<_, Account>("UPDATE accounts SET is_archived = ? WHERE id = ? RETURNING *")
-            .bind(new_archived_state)
-            .bind(id)
-            .fetch_one(pool)
-            .await
-            .map_err(|e| e.to_string())
+        let mut tx = pool.begin().await?;
+        let updated = sqlx::query_as::<_, Account>(
+            "UPDATE accounts SET is_archived = ? WHERE id = ? RETURNING *",
+        )
+        .bind(new_archived_state)
+        .bind(&id)
+        .fetch_one(&mut *tx)
+        .await?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_ACCOUNT,
+            &id,
+            Some(&account),
+            Some(&updated),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(updated)
     }
 }
 
@@ -221,7 +372,10 @@ mod tests {
         assert!(duplicate_result.is_err());
         assert_eq!(
             duplicate_result.unwrap_err(),
-            "Account with name 'Test Bank Updated' already exists"
+            AppError::conflict(
+                "Account",
+                "Account with name 'Test Bank Updated' already exists"
+            )
         );
 
         // 6. Delete
@@ -0,0 +1,889 @@
+use crate::error::AppError;
+use crate::models::{
+    Account, AuditLogEntry, BalanceSheet, Budget, BudgetCategory, Contribution, CurrencyRate,
+    Entry, MonthlyIncome, RecurringRule, RetirementPlan, RetirementPlanProjection, Transaction,
+    UserSettings,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Sqlite, SqlitePool};
+use std::collections::HashMap;
+
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/**
+ * Portable snapshot of every user-owned table.
+ * `schema_version` is the latest sqlx migration applied when the archive was written,
+ * so archives from older builds can be recognised and restored into newer databases.
+ * Table lists default to empty so archives written before a table existed still parse.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseArchive {
+    pub format_version: u32,
+    pub schema_version: i64,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub user_settings: Vec<UserSettings>,
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    #[serde(default)]
+    pub balance_sheets: Vec<BalanceSheet>,
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+    #[serde(default)]
+    pub recurring_rules: Vec<RecurringRule>,
+    #[serde(default)]
+    pub budget_categories: Vec<BudgetCategory>,
+    #[serde(default)]
+    pub budgets: Vec<Budget>,
+    #[serde(default)]
+    pub contributions: Vec<Contribution>,
+    #[serde(default)]
+    pub monthly_income: Vec<MonthlyIncome>,
+    #[serde(default)]
+    pub currency_rates: Vec<CurrencyRate>,
+    #[serde(default)]
+    pub retirement_plans: Vec<RetirementPlan>,
+    #[serde(default)]
+    pub retirement_plan_projections: Vec<RetirementPlanProjection>,
+    #[serde(default)]
+    pub audit_log: Vec<AuditLogEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictMode {
+    // Wipe the existing data and restore the archive as-is
+    Replace,
+    // Keep existing rows and only add the archived rows that don't conflict.
+    // Archived accounts, sheets and budget categories whose name or year is already taken
+    // are skipped, and their children are attached to the existing row instead.
+    Merge,
+    // Fail (and roll back) on the first archived row that conflicts with existing data
+    Abort,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveTableReport {
+    pub table: String,
+    pub inserted: u64,
+    pub skipped: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveImportReport {
+    pub mode: ConflictMode,
+    pub schema_version: i64,
+    pub tables: Vec<ArchiveTableReport>,
+}
+
+// Child tables first so deletes never trip foreign keys
+const ARCHIVE_TABLES_DELETE_ORDER: &[&str] = &[
+    "retirement_plan_projections",
+    "retirement_plans",
+    "entries",
+    "transactions",
+    "recurring_rules",
+    "budgets",
+    "budget_categories",
+    "contributions",
+    "monthly_income",
+    "currency_rates",
+    "balance_sheets",
+    "accounts",
+    "user_settings",
+    "audit_log",
+];
+
+pub struct ArchiveService;
+
+impl ArchiveService {
+    pub fn current_schema_version() -> i64 {
+        sqlx::migrate!("./migrations")
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub async fn export_all(pool: &SqlitePool) -> Result<DatabaseArchive, AppError> {
+        // Read everything from a single transaction so the snapshot is consistent
+        let mut tx = pool.begin().await?;
+
+        let user_settings = sqlx::query_as::<_, UserSettings>("SELECT * FROM user_settings")
+            .fetch_all(&mut *tx)
+            .await?;
+        let accounts =
+            sqlx::query_as::<_, Account>("SELECT * FROM accounts ORDER BY sort_order ASC")
+                .fetch_all(&mut *tx)
+                .await?;
+        let balance_sheets =
+            sqlx::query_as::<_, BalanceSheet>("SELECT * FROM balance_sheets ORDER BY year ASC")
+                .fetch_all(&mut *tx)
+                .await?;
+        let entries = sqlx::query_as::<_, Entry>("SELECT * FROM entries")
+            .fetch_all(&mut *tx)
+            .await?;
+        let transactions =
+            sqlx::query_as::<_, Transaction>("SELECT * FROM transactions ORDER BY date ASC")
+                .fetch_all(&mut *tx)
+                .await?;
+        let recurring_rules = sqlx::query_as::<_, RecurringRule>("SELECT * FROM recurring_rules")
+            .fetch_all(&mut *tx)
+            .await?;
+        let budget_categories = sqlx::query_as::<_, BudgetCategory>(
+            "SELECT * FROM budget_categories ORDER BY name ASC",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let budgets =
+            sqlx::query_as::<_, Budget>("SELECT * FROM budgets ORDER BY year ASC, month ASC")
+                .fetch_all(&mut *tx)
+                .await?;
+        let contributions = sqlx::query_as::<_, Contribution>(
+            "SELECT * FROM contributions ORDER BY year ASC, month ASC",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let monthly_income = sqlx::query_as::<_, MonthlyIncome>(
+            "SELECT * FROM monthly_income ORDER BY year ASC, month ASC",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let currency_rates = sqlx::query_as::<_, CurrencyRate>(
+            "SELECT * FROM currency_rates ORDER BY year ASC, month ASC",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let retirement_plans =
+            sqlx::query_as::<_, RetirementPlan>("SELECT * FROM retirement_plans")
+                .fetch_all(&mut *tx)
+                .await?;
+        let retirement_plan_projections = sqlx::query_as::<_, RetirementPlanProjection>(
+            "SELECT * FROM retirement_plan_projections ORDER BY plan_id, year ASC, month ASC",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let audit_log =
+            sqlx::query_as::<_, AuditLogEntry>("SELECT * FROM audit_log ORDER BY created_at ASC")
+                .fetch_all(&mut *tx)
+                .await?;
+
+        tx.commit().await?;
+
+        Ok(DatabaseArchive {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            schema_version: Self::current_schema_version(),
+            exported_at: chrono::Utc::now(),
+            user_settings,
+            accounts,
+            balance_sheets,
+            entries,
+            transactions,
+            recurring_rules,
+            budget_categories,
+            budgets,
+            contributions,
+            monthly_income,
+            currency_rates,
+            retirement_plans,
+            retirement_plan_projections,
+            audit_log,
+        })
+    }
+
+    pub async fn import_all(
+        pool: &SqlitePool,
+        archive: DatabaseArchive,
+        mode: ConflictMode,
+    ) -> Result<ArchiveImportReport, AppError> {
+        if archive.format_version > ARCHIVE_FORMAT_VERSION {
+            return Err(AppError::validation(
+                "formatVersion",
+                format!(
+                    "Archive format version {} is not supported (latest is {ARCHIVE_FORMAT_VERSION})",
+                    archive.format_version
+                ),
+            ));
+        }
+
+        let current_schema_version = Self::current_schema_version();
+        if archive.schema_version > current_schema_version {
+            return Err(AppError::validation(
+                "schemaVersion",
+                format!(
+                    "Archive schema version {} is newer than this app's schema version {current_schema_version}",
+                    archive.schema_version
+                ),
+            ));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        if mode == ConflictMode::Replace {
+            for table in ARCHIVE_TABLES_DELETE_ORDER {
+                sqlx::query(&format!("DELETE FROM {table}"))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        let mut tables = Vec::new();
+        tables.push(Self::import_user_settings(&mut tx, &archive.user_settings, mode).await?);
+
+        let mut report = ArchiveTableReport::new("accounts");
+        let sql = Self::insert_sql(
+            "accounts",
+            "id, name, account_type, sub_category, currency, sort_order, is_archived, created_at, deleted_at",
+            9,
+            mode,
+        );
+        // Archived id -> id of the existing row it was merged into
+        let mut account_ids: HashMap<String, String> = HashMap::new();
+        for account in &archive.accounts {
+            if mode == ConflictMode::Merge {
+                if let Some(existing_id) = Self::existing_id(
+                    &mut tx,
+                    "accounts",
+                    "name",
+                    &account.id,
+                    account.name.clone(),
+                )
+                .await?
+                {
+                    account_ids.insert(account.id.clone(), existing_id);
+                    report.skipped += 1;
+                    continue;
+                }
+            }
+            let query = sqlx::query(&sql)
+                .bind(&account.id)
+                .bind(&account.name)
+                .bind(&account.account_type)
+                .bind(&account.sub_category)
+                .bind(&account.currency)
+                .bind(account.sort_order)
+                .bind(account.is_archived)
+                .bind(account.created_at)
+                .bind(account.deleted_at);
+            report.record(Self::execute_insert(&mut tx, query, "accounts", &account.id).await?);
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("balance_sheets");
+        let sql = Self::insert_sql(
+            "balance_sheets",
+            "id, year, created_at, deleted_at",
+            4,
+            mode,
+        );
+        let mut sheet_ids: HashMap<String, String> = HashMap::new();
+        for sheet in &archive.balance_sheets {
+            if mode == ConflictMode::Merge {
+                if let Some(existing_id) =
+                    Self::existing_id(&mut tx, "balance_sheets", "year", &sheet.id, sheet.year)
+                        .await?
+                {
+                    sheet_ids.insert(sheet.id.clone(), existing_id);
+                    report.skipped += 1;
+                    continue;
+                }
+            }
+            let query = sqlx::query(&sql)
+                .bind(&sheet.id)
+                .bind(sheet.year)
+                .bind(sheet.created_at)
+                .bind(sheet.deleted_at);
+            report.record(Self::execute_insert(&mut tx, query, "balance_sheets", &sheet.id).await?);
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("entries");
+        let sql = Self::insert_sql(
+            "entries",
+            "id, balance_sheet_id, account_id, month, amount, updated_at, status, source, note",
+            9,
+            mode,
+        );
+        for entry in &archive.entries {
+            let query = sqlx::query(&sql)
+                .bind(&entry.id)
+                .bind(Self::mapped(&sheet_ids, &entry.balance_sheet_id))
+                .bind(Self::mapped(&account_ids, &entry.account_id))
+                .bind(entry.month)
+                .bind(entry.amount)
+                .bind(entry.updated_at)
+                .bind(&entry.status)
+                .bind(&entry.source)
+                .bind(&entry.note);
+            report.record(Self::execute_insert(&mut tx, query, "entries", &entry.id).await?);
+        }
+        tables.push(report);
+
+        // transfer_id is a deferred foreign key, so transfer legs can be inserted in any order
+        let mut report = ArchiveTableReport::new("transactions");
+        let sql = Self::insert_sql(
+            "transactions",
+            "id, account_id, date, amount, payee, category, memo, transfer_id, created_at, updated_at",
+            10,
+            mode,
+        );
+        for transaction in &archive.transactions {
+            let query = sqlx::query(&sql)
+                .bind(&transaction.id)
+                .bind(Self::mapped(&account_ids, &transaction.account_id))
+                .bind(transaction.date)
+                .bind(transaction.amount)
+                .bind(&transaction.payee)
+                .bind(&transaction.category)
+                .bind(&transaction.memo)
+                .bind(&transaction.transfer_id)
+                .bind(transaction.created_at)
+                .bind(transaction.updated_at);
+            report.record(
+                Self::execute_insert(&mut tx, query, "transactions", &transaction.id).await?,
+            );
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("recurring_rules");
+        let sql = Self::insert_sql(
+            "recurring_rules",
+            "id, account_id, rule_type, amount, rate, is_active, created_at, updated_at",
+            8,
+            mode,
+        );
+        for rule in &archive.recurring_rules {
+            let query = sqlx::query(&sql)
+                .bind(&rule.id)
+                .bind(Self::mapped(&account_ids, &rule.account_id))
+                .bind(&rule.rule_type)
+                .bind(rule.amount)
+                .bind(rule.rate)
+                .bind(rule.is_active)
+                .bind(rule.created_at)
+                .bind(rule.updated_at);
+            report.record(Self::execute_insert(&mut tx, query, "recurring_rules", &rule.id).await?);
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("budget_categories");
+        let sql = Self::insert_sql(
+            "budget_categories",
+            "id, name, rollover, created_at",
+            4,
+            mode,
+        );
+        let mut category_ids: HashMap<String, String> = HashMap::new();
+        for category in &archive.budget_categories {
+            if mode == ConflictMode::Merge {
+                if let Some(existing_id) = Self::existing_id(
+                    &mut tx,
+                    "budget_categories",
+                    "name",
+                    &category.id,
+                    category.name.clone(),
+                )
+                .await?
+                {
+                    category_ids.insert(category.id.clone(), existing_id);
+                    report.skipped += 1;
+                    continue;
+                }
+            }
+            let query = sqlx::query(&sql)
+                .bind(&category.id)
+                .bind(&category.name)
+                .bind(category.rollover)
+                .bind(category.created_at);
+            report.record(
+                Self::execute_insert(&mut tx, query, "budget_categories", &category.id).await?,
+            );
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("budgets");
+        let sql = Self::insert_sql(
+            "budgets",
+            "id, category_id, year, month, amount, rollover_amount, updated_at",
+            7,
+            mode,
+        );
+        for budget in &archive.budgets {
+            let query = sqlx::query(&sql)
+                .bind(&budget.id)
+                .bind(Self::mapped(&category_ids, &budget.category_id))
+                .bind(budget.year)
+                .bind(budget.month)
+                .bind(budget.amount)
+                .bind(budget.rollover_amount)
+                .bind(budget.updated_at);
+            report.record(Self::execute_insert(&mut tx, query, "budgets", &budget.id).await?);
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("contributions");
+        let sql = Self::insert_sql(
+            "contributions",
+            "id, account_id, year, month, amount, updated_at",
+            6,
+            mode,
+        );
+        for contribution in &archive.contributions {
+            let query = sqlx::query(&sql)
+                .bind(&contribution.id)
+                .bind(Self::mapped(&account_ids, &contribution.account_id))
+                .bind(contribution.year)
+                .bind(contribution.month)
+                .bind(contribution.amount)
+                .bind(contribution.updated_at);
+            report.record(
+                Self::execute_insert(&mut tx, query, "contributions", &contribution.id).await?,
+            );
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("monthly_income");
+        let sql = Self::insert_sql(
+            "monthly_income",
+            "id, year, month, amount, updated_at",
+            5,
+            mode,
+        );
+        for income in &archive.monthly_income {
+            let query = sqlx::query(&sql)
+                .bind(&income.id)
+                .bind(income.year)
+                .bind(income.month)
+                .bind(income.amount)
+                .bind(income.updated_at);
+            report
+                .record(Self::execute_insert(&mut tx, query, "monthly_income", &income.id).await?);
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("currency_rates");
+        let sql = Self::insert_sql(
+            "currency_rates",
+            "id, from_currency, to_currency, provider, rate, month, year, timestamp",
+            8,
+            mode,
+        );
+        for rate in &archive.currency_rates {
+            let query = sqlx::query(&sql)
+                .bind(&rate.id)
+                .bind(&rate.from_currency)
+                .bind(&rate.to_currency)
+                .bind(&rate.provider)
+                .bind(rate.rate)
+                .bind(rate.month)
+                .bind(rate.year)
+                .bind(rate.timestamp);
+            report.record(Self::execute_insert(&mut tx, query, "currency_rates", &rate.id).await?);
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("retirement_plans");
+        let sql = Self::insert_sql(
+            "retirement_plans",
+            "id, name, target_retirement_date, starting_net_worth, monthly_contribution, expected_monthly_expenses, return_scenario, inflation_rate, created_at, updated_at, deleted_at",
+            11,
+            mode,
+        );
+        for plan in &archive.retirement_plans {
+            let query = sqlx::query(&sql)
+                .bind(&plan.id)
+                .bind(&plan.name)
+                .bind(plan.target_retirement_date)
+                .bind(plan.starting_net_worth)
+                .bind(plan.monthly_contribution)
+                .bind(plan.expected_monthly_expenses)
+                .bind(&plan.return_scenario)
+                .bind(plan.inflation_rate)
+                .bind(plan.created_at)
+                .bind(plan.updated_at)
+                .bind(plan.deleted_at);
+            report
+                .record(Self::execute_insert(&mut tx, query, "retirement_plans", &plan.id).await?);
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("retirement_plan_projections");
+        let sql = Self::insert_sql(
+            "retirement_plan_projections",
+            "id, plan_id, year, month, projected_net_worth, created_at",
+            6,
+            mode,
+        );
+        for projection in &archive.retirement_plan_projections {
+            let query = sqlx::query(&sql)
+                .bind(&projection.id)
+                .bind(&projection.plan_id)
+                .bind(projection.year)
+                .bind(projection.month)
+                .bind(projection.projected_net_worth)
+                .bind(projection.created_at);
+            report.record(
+                Self::execute_insert(
+                    &mut tx,
+                    query,
+                    "retirement_plan_projections",
+                    &projection.id,
+                )
+                .await?,
+            );
+        }
+        tables.push(report);
+
+        let mut report = ArchiveTableReport::new("audit_log");
+        let sql = Self::insert_sql(
+            "audit_log",
+            "id, entity_type, entity_id, action, old_value, new_value, origin, account_id, year, month, created_at",
+            11,
+            mode,
+        );
+        for record in &archive.audit_log {
+            let query = sqlx::query(&sql)
+                .bind(&record.id)
+                .bind(&record.entity_type)
+                .bind(&record.entity_id)
+                .bind(&record.action)
+                .bind(&record.old_value)
+                .bind(&record.new_value)
+                .bind(&record.origin)
+                .bind(
+                    record
+                        .account_id
+                        .as_deref()
+                        .map(|account_id| Self::mapped(&account_ids, account_id)),
+                )
+                .bind(record.year)
+                .bind(record.month)
+                .bind(record.created_at);
+            report.record(Self::execute_insert(&mut tx, query, "audit_log", &record.id).await?);
+        }
+        tables.push(report);
+
+        tx.commit().await?;
+
+        Ok(ArchiveImportReport {
+            mode,
+            schema_version: archive.schema_version,
+            tables,
+        })
+    }
+
+    // User settings are a singleton, so conflicts are decided on existence rather than id
+    async fn import_user_settings(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        settings: &[UserSettings],
+        mode: ConflictMode,
+    ) -> Result<ArchiveTableReport, AppError> {
+        let mut report = ArchiveTableReport::new("user_settings");
+        let Some(settings) = settings.first() else {
+            return Ok(report);
+        };
+
+        let existing: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM user_settings")
+            .fetch_one(&mut **tx)
+            .await?;
+
+        if existing.0 > 0 {
+            match mode {
+                ConflictMode::Abort => {
+                    return Err(AppError::conflict(
+                        "user_settings",
+                        "Import aborted: user settings already exist in this database",
+                    ))
+                }
+                _ => {
+                    report.skipped += 1;
+                    return Ok(report);
+                }
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO user_settings (id, name, home_currency, theme, needs_exchange_sync, backup_retention, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&settings.id)
+        .bind(&settings.name)
+        .bind(&settings.home_currency)
+        .bind(&settings.theme)
+        .bind(settings.needs_exchange_sync)
+        .bind(settings.backup_retention)
+        .bind(settings.created_at)
+        .bind(settings.updated_at)
+        .execute(&mut **tx)
+        .await?;
+        report.inserted += 1;
+
+        Ok(report)
+    }
+
+    // Id of a stored row that already holds `key` under a different id, if any
+    async fn existing_id<K>(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        table: &str,
+        key_column: &str,
+        id: &str,
+        key: K,
+    ) -> Result<Option<String>, AppError>
+    where
+        K: for<'q> sqlx::Encode<'q, Sqlite> + sqlx::Type<Sqlite> + Send,
+    {
+        sqlx::query_scalar::<_, String>(&format!(
+            "SELECT id FROM {table} WHERE {key_column} = ? AND id != ?"
+        ))
+        .bind(key)
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::from)
+    }
+
+    fn mapped<'a>(ids: &'a HashMap<String, String>, id: &'a str) -> &'a str {
+        ids.get(id).map_or(id, String::as_str)
+    }
+
+    fn insert_sql(table: &str, columns: &str, column_count: usize, mode: ConflictMode) -> String {
+        let placeholders = vec!["?"; column_count].join(", ");
+        let on_conflict = match mode {
+            ConflictMode::Merge => " ON CONFLICT DO NOTHING",
+            ConflictMode::Replace | ConflictMode::Abort => "",
+        };
+        format!("INSERT INTO {table} ({columns}) VALUES ({placeholders}){on_conflict}")
+    }
+
+    // Returns whether the row was inserted (false when skipped by a merge)
+    async fn execute_insert<'q>(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        query: sqlx::query::Query<'q, Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+        table: &str,
+        id: &str,
+    ) -> Result<bool, AppError> {
+        match query.execute(&mut **tx).await {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Err(AppError::conflict(
+                    table,
+                    format!("Import aborted: {table} row {id} conflicts with existing data"),
+                ))
+            }
+            Err(e) => Err(AppError::Database(format!(
+                "Failed to import {table} row {id}: {e}"
+            ))),
+        }
+    }
+}
+
+impl ArchiveTableReport {
+    fn new(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            inserted: 0,
+            skipped: 0,
+        }
+    }
+
+    fn record(&mut self, inserted: bool) {
+        if inserted {
+            self.inserted += 1;
+        } else {
+            self.skipped += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        account::AccountService, balance_sheet::BalanceSheetService,
+        currency_rates::currency_rate::CurrencyRateService, entry::EntryService,
+        retirement_plan::RetirementPlanService, user_settings::UserSettingsService,
+    };
+    use crate::test_utils::setup_test_db;
+
+    async fn seed(pool: &SqlitePool) {
+        UserSettingsService::upsert(pool, "User".into(), "NZD".into(), "system".into())
+            .await
+            .expect("settings");
+        let account = AccountService::upsert(
+            pool,
+            None,
+            "Checking".into(),
+            "Asset".into(),
+            "USD".into(),
+            Some("cash".into()),
+        )
+        .await
+        .expect("account");
+        let sheet = BalanceSheetService::upsert(pool, None, 2024)
+            .await
+            .expect("sheet");
+        EntryService::upsert(pool, sheet.id, account.id, 3, 1234.5, Default::default())
+            .await
+            .expect("entry");
+        CurrencyRateService::upsert(
+            pool,
+            None,
+            "USD".into(),
+            "NZD".into(),
+            "manual".into(),
+            1.6,
+            3,
+            2024,
+        )
+        .await
+        .expect("rate");
+        RetirementPlanService::create(
+            pool,
+            "Plan".into(),
+            Some(2050),
+            100_000.0,
+            1_000.0,
+            3_000.0,
+            "moderate".into(),
+            2.0,
+        )
+        .await
+        .expect("plan");
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source = setup_test_db().await;
+        seed(&source).await;
+
+        let archive = ArchiveService::export_all(&source).await.expect("export");
+        assert_eq!(
+            archive.schema_version,
+            ArchiveService::current_schema_version()
+        );
+        assert_eq!(archive.accounts.len(), 1);
+        assert_eq!(archive.entries.len(), 1);
+
+        // Serialize through JSON to exercise the portable format
+        let json = serde_json::to_string(&archive).expect("serialize");
+        let archive: DatabaseArchive = serde_json::from_str(&json).expect("deserialize");
+
+        let target = setup_test_db().await;
+        let report = ArchiveService::import_all(&target, archive, ConflictMode::Abort)
+            .await
+            .expect("import");
+        assert!(report.tables.iter().all(|t| t.skipped == 0));
+
+        let entries = EntryService::get_all(&target).await.expect("entries");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, 1234.5);
+        let accounts = AccountService::get_all(&target, true)
+            .await
+            .expect("accounts");
+        assert_eq!(accounts[0].sub_category.as_deref(), Some("cash"));
+        let plans = RetirementPlanService::get_all(&target)
+            .await
+            .expect("plans");
+        assert_eq!(plans.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_abort_rolls_back_on_conflict() {
+        let pool = setup_test_db().await;
+        seed(&pool).await;
+        let archive = ArchiveService::export_all(&pool).await.expect("export");
+
+        let result = ArchiveService::import_all(&pool, archive, ConflictMode::Abort).await;
+        assert!(result.is_err());
+
+        let accounts = AccountService::get_all(&pool, true)
+            .await
+            .expect("accounts");
+        assert_eq!(accounts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_merge_skips_existing_rows() {
+        let pool = setup_test_db().await;
+        seed(&pool).await;
+        let archive = ArchiveService::export_all(&pool).await.expect("export");
+
+        let report = ArchiveService::import_all(&pool, archive, ConflictMode::Merge)
+            .await
+            .expect("merge");
+        assert!(report.tables.iter().all(|t| t.inserted == 0));
+
+        let entries = EntryService::get_all(&pool).await.expect("entries");
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_merge_attaches_children_to_existing_parents() {
+        let source = setup_test_db().await;
+        seed(&source).await;
+        let archive = ArchiveService::export_all(&source).await.expect("export");
+
+        // Same account name and sheet year, but created separately so the ids differ
+        let pool = setup_test_db().await;
+        let account = AccountService::upsert(
+            &pool,
+            None,
+            "Checking".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("account");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+
+        let report = ArchiveService::import_all(&pool, archive, ConflictMode::Merge)
+            .await
+            .expect("merge");
+        let table = |name: &str| {
+            report
+                .tables
+                .iter()
+                .find(|table| table.table == name)
+                .map(|table| (table.inserted, table.skipped))
+        };
+        assert_eq!(table("accounts"), Some((0, 1)));
+        assert_eq!(table("balance_sheets"), Some((0, 1)));
+        assert_eq!(table("entries"), Some((1, 0)));
+
+        let accounts = AccountService::get_all(&pool, true)
+            .await
+            .expect("accounts");
+        assert_eq!(accounts.len(), 1);
+        let entries = EntryService::get_all(&pool).await.expect("entries");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].account_id, account.id);
+        assert_eq!(entries[0].balance_sheet_id, sheet.id);
+    }
+
+    #[tokio::test]
+    async fn test_import_replace_and_schema_version_checks() {
+        let pool = setup_test_db().await;
+        seed(&pool).await;
+        let mut archive = ArchiveService::export_all(&pool).await.expect("export");
+        archive.entries.clear();
+
+        ArchiveService::import_all(&pool, archive, ConflictMode::Replace)
+            .await
+            .expect("replace");
+        let entries = EntryService::get_all(&pool).await.expect("entries");
+        assert!(entries.is_empty());
+
+        let mut newer = ArchiveService::export_all(&pool).await.expect("export");
+        newer.schema_version = ArchiveService::current_schema_version() + 1;
+        let result = ArchiveService::import_all(&pool, newer, ConflictMode::Merge).await;
+        assert!(result.is_err());
+    }
+}
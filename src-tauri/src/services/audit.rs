@@ -0,0 +1,324 @@
+use crate::error::AppError;
+use crate::models::{AuditLogEntry, Entry};
+use crate::services::balance_sheet::BalanceSheetService;
+use crate::services::entry::{EntryProvenance, EntryService};
+use serde::Serialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::future::Future;
+use uuid::Uuid;
+
+pub const AUDIT_ENTITY_ENTRY: &str = "entry";
+pub const AUDIT_ENTITY_ACCOUNT: &str = "account";
+pub const AUDIT_ENTITY_CURRENCY_RATE: &str = "currency_rate";
+pub const AUDIT_ENTITY_RETIREMENT_PLAN: &str = "retirement_plan";
+
+pub const AUDIT_ACTION_INSERT: &str = "insert";
+pub const AUDIT_ACTION_UPDATE: &str = "update";
+pub const AUDIT_ACTION_DELETE: &str = "delete";
+
+tokio::task_local! {
+    static AUDIT_ORIGIN: &'static str;
+}
+
+/**
+ * Runs `future` with `origin` (usually the command name) stamped on every audit row it writes.
+ * Changes made outside any origin scope are recorded with no origin.
+ */
+pub async fn with_origin<F: Future>(origin: &'static str, future: F) -> F::Output {
+    AUDIT_ORIGIN.scope(origin, future).await
+}
+
+pub struct AuditService;
+
+impl AuditService {
+    /**
+     * Records a change to a row: `old` is None for inserts and `new` is None for deletes.
+     * Updates that leave the row unchanged aren't recorded.
+     * Pass the connection of the transaction making the change so both commit together.
+     */
+    pub async fn record<T: Serialize>(
+        conn: &mut SqliteConnection,
+        entity_type: &str,
+        entity_id: &str,
+        old: Option<&T>,
+        new: Option<&T>,
+    ) -> Result<(), AppError> {
+        Self::insert(conn, entity_type, entity_id, old, new, None).await
+    }
+
+    // Entries are also keyed by account and month so a cell's history can be browsed
+    pub async fn record_entry(
+        conn: &mut SqliteConnection,
+        old: Option<&Entry>,
+        new: Option<&Entry>,
+    ) -> Result<(), AppError> {
+        let Some(entry) = new.or(old) else {
+            return Ok(());
+        };
+        let year: Option<(i32,)> = sqlx::query_as("SELECT year FROM balance_sheets WHERE id = ?")
+            .bind(&entry.balance_sheet_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        Self::insert(
+            conn,
+            AUDIT_ENTITY_ENTRY,
+            &entry.id,
+            old,
+            new,
+            Some((&entry.account_id, year.map(|(year,)| year), entry.month)),
+        )
+        .await
+    }
+
+    async fn insert<T: Serialize>(
+        conn: &mut SqliteConnection,
+        entity_type: &str,
+        entity_id: &str,
+        old: Option<&T>,
+        new: Option<&T>,
+        entry_key: Option<(&str, Option<i32>, u32)>,
+    ) -> Result<(), AppError> {
+        let action = match (old, new) {
+            (None, Some(_)) => AUDIT_ACTION_INSERT,
+            (Some(_), Some(_)) => AUDIT_ACTION_UPDATE,
+            (Some(_), None) => AUDIT_ACTION_DELETE,
+            (None, None) => return Ok(()),
+        };
+        let to_json = |value: Option<&T>| {
+            value
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(|e| AppError::invalid(format!("Failed to serialize audit value: {e}")))
+        };
+        let old_value = to_json(old)?;
+        let new_value = to_json(new)?;
+        if action == AUDIT_ACTION_UPDATE
+            && Self::without_timestamps(&old_value) == Self::without_timestamps(&new_value)
+        {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO audit_log (id, entity_type, entity_id, action, old_value, new_value, origin, account_id, year, month, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(action)
+        .bind(old_value.map(|value| value.to_string()))
+        .bind(new_value.map(|value| value.to_string()))
+        .bind(AUDIT_ORIGIN.try_with(|origin| *origin).ok())
+        .bind(entry_key.map(|(account_id, _, _)| account_id))
+        .bind(entry_key.and_then(|(_, year, _)| year))
+        .bind(entry_key.map(|(_, _, month)| month))
+        .bind(chrono::Utc::now())
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    // Saving a row with the same values only bumps its timestamp, which isn't a change worth logging
+    fn without_timestamps(value: &Option<serde_json::Value>) -> Option<serde_json::Value> {
+        let mut value = value.clone()?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("updatedAt");
+            fields.remove("timestamp");
+        }
+        Some(value)
+    }
+
+    // LIST (newest first) for any audited row
+    pub async fn get_history(
+        pool: &SqlitePool,
+        entity_type: String,
+        entity_id: String,
+    ) -> Result<Vec<AuditLogEntry>, AppError> {
+        sqlx::query_as::<_, AuditLogEntry>(
+            "SELECT * FROM audit_log WHERE entity_type = ? AND entity_id = ? ORDER BY created_at DESC, rowid DESC",
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // LIST (newest first) for one account-month, across re-created entries
+    pub async fn get_entry_history(
+        pool: &SqlitePool,
+        account_id: String,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<AuditLogEntry>, AppError> {
+        sqlx::query_as::<_, AuditLogEntry>(
+            "SELECT * FROM audit_log WHERE entity_type = ? AND account_id = ? AND year = ? AND month = ? ORDER BY created_at DESC, rowid DESC",
+        )
+        .bind(AUDIT_ENTITY_ENTRY)
+        .bind(account_id)
+        .bind(year)
+        .bind(month)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    /**
+     * Puts an account-month back to the value it had before the given change.
+     * Reverting an insert deletes the entry again. The revert is recorded like any other change.
+     */
+    pub async fn revert_entry(
+        pool: &SqlitePool,
+        audit_id: String,
+    ) -> Result<Option<Entry>, AppError> {
+        let record = sqlx::query_as::<_, AuditLogEntry>("SELECT * FROM audit_log WHERE id = ?")
+            .bind(&audit_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::not_found("AuditLogEntry", audit_id))?;
+
+        let (Some(account_id), Some(year), Some(month)) =
+            (record.account_id, record.year, record.month)
+        else {
+            return Err(AppError::validation(
+                "auditId",
+                "Only changes to entries can be reverted",
+            ));
+        };
+        let sheet = BalanceSheetService::get_by_year(pool, year)
+            .await?
+            .ok_or_else(|| AppError::not_found("BalanceSheet", year.to_string()))?;
+
+        match record.old_value {
+            Some(old_value) => {
+                let old: Entry = serde_json::from_str(&old_value).map_err(|e| {
+                    AppError::invalid(format!("Audit record has an unreadable value: {e}"))
+                })?;
+                let entry = EntryService::upsert(
+                    pool,
+                    sheet.id,
+                    account_id,
+                    month as i32,
+                    old.amount,
+                    EntryProvenance {
+                        status: old.status,
+                        source: old.source,
+                        note: old.note,
+                    },
+                )
+                .await?;
+                Ok(Some(entry))
+            }
+            None => {
+                if let Some(current) =
+                    EntryService::get_by_key(pool, &sheet.id, &account_id, month as i32).await?
+                {
+                    EntryService::delete(pool, current.id).await?;
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::account::AccountService;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn test_entry_history_and_revert() {
+        let pool = setup_test_db().await;
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet");
+        let account =
+            AccountService::upsert(&pool, None, "A".into(), "Asset".into(), "NZD".into(), None)
+                .await
+                .expect("account");
+
+        with_origin(
+            "upsert_entry",
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account.id.clone(),
+                3,
+                100.0,
+                Default::default(),
+            ),
+        )
+        .await
+        .expect("create");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            account.id.clone(),
+            3,
+            250.0,
+            Default::default(),
+        )
+        .await
+        .expect("update");
+        // Unchanged values aren't recorded
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            account.id.clone(),
+            3,
+            250.0,
+            Default::default(),
+        )
+        .await
+        .expect("no-op");
+
+        let history = AuditService::get_entry_history(&pool, account.id.clone(), 2025, 3)
+            .await
+            .expect("history");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, AUDIT_ACTION_UPDATE);
+        assert_eq!(history[1].action, AUDIT_ACTION_INSERT);
+        assert_eq!(history[1].origin.as_deref(), Some("upsert_entry"));
+        assert_eq!(history[0].origin, None);
+
+        // Undo the update
+        let reverted = AuditService::revert_entry(&pool, history[0].id.clone())
+            .await
+            .expect("revert update")
+            .expect("entry restored");
+        assert_eq!(reverted.amount, 100.0);
+
+        // Undo the insert
+        let removed = AuditService::revert_entry(&pool, history[1].id.clone())
+            .await
+            .expect("revert insert");
+        assert!(removed.is_none());
+        assert!(EntryService::get_by_key(&pool, &sheet.id, &account.id, 3)
+            .await
+            .expect("lookup")
+            .is_none());
+
+        let history = AuditService::get_entry_history(&pool, account.id.clone(), 2025, 3)
+            .await
+            .expect("history after reverts");
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].action, AUDIT_ACTION_DELETE);
+
+        // Trashing then purging the account records both steps
+        AccountService::delete(&pool, account.id.clone())
+            .await
+            .expect("trash account");
+        AccountService::purge(&pool, account.id.clone())
+            .await
+            .expect("purge account");
+        let account_history =
+            AuditService::get_history(&pool, AUDIT_ENTITY_ACCOUNT.into(), account.id)
+                .await
+                .expect("account history");
+        assert_eq!(account_history.len(), 3);
+        assert_eq!(account_history[1].action, AUDIT_ACTION_UPDATE);
+        assert_eq!(account_history[0].action, AUDIT_ACTION_DELETE);
+        assert!(account_history[0].new_value.is_none());
+    }
+}
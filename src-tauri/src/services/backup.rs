@@ -0,0 +1,348 @@
+use crate::error::AppError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const BACKUP_DIR_NAME: &str = "backups";
+pub const DEFAULT_BACKUP_RETENTION: i64 = 7;
+pub const BACKUP_INTERVAL_HOURS: i64 = 24;
+
+pub const BACKUP_REASON_PRE_MIGRATION: &str = "pre-migration";
+pub const BACKUP_REASON_SCHEDULED: &str = "scheduled";
+pub const BACKUP_REASON_PRE_RESTORE: &str = "pre-restore";
+pub const BACKUP_REASON_MANUAL: &str = "manual";
+
+const BACKUP_FILE_PREFIX: &str = "oink-backup-";
+const BACKUP_FILE_EXTENSION: &str = ".db";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3f";
+const PENDING_RESTORE_SUFFIX: &str = "restore";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+pub struct BackupService;
+
+impl BackupService {
+    /**
+     * Snapshots the live database into `backup_dir` using `VACUUM INTO`,
+     * which produces a consistent, compacted copy without blocking readers.
+     */
+    pub async fn create_backup(
+        pool: &SqlitePool,
+        backup_dir: &Path,
+        reason: &str,
+    ) -> Result<BackupInfo, AppError> {
+        fs::create_dir_all(backup_dir)
+            .map_err(|e| AppError::Io(format!("Failed to create backup dir: {e}")))?;
+
+        let created_at = chrono::Utc::now();
+        let file_name = format!(
+            "{BACKUP_FILE_PREFIX}{}-{reason}{BACKUP_FILE_EXTENSION}",
+            created_at.format(BACKUP_TIMESTAMP_FORMAT)
+        );
+        let path = backup_dir.join(&file_name);
+
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy().to_string())
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to back up database: {e}")))?;
+
+        println!("[Backup] Created {file_name}");
+
+        Self::backup_info(&path)
+            .ok_or_else(|| AppError::Io(format!("Backup {file_name} was not written")))
+    }
+
+    // Backs up only when there is existing data and at least one migration is pending
+    pub async fn create_pre_migration_backup(
+        pool: &SqlitePool,
+        backup_dir: &Path,
+        retention: i64,
+    ) -> Result<Option<BackupInfo>, AppError> {
+        let has_migrations_table: Option<(String,)> = sqlx::query_as(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        if has_migrations_table.is_none() {
+            return Ok(None);
+        }
+
+        let applied: Vec<(i64,)> = sqlx::query_as("SELECT version FROM _sqlx_migrations")
+            .fetch_all(pool)
+            .await?;
+        let applied: HashSet<i64> = applied.into_iter().map(|(version,)| version).collect();
+
+        let has_pending = sqlx::migrate!("./migrations")
+            .iter()
+            .any(|migration| !applied.contains(&migration.version));
+
+        if !has_pending {
+            return Ok(None);
+        }
+
+        let backup = Self::create_backup(pool, backup_dir, BACKUP_REASON_PRE_MIGRATION).await?;
+        Self::prune_backups(backup_dir, retention)?;
+        Ok(Some(backup))
+    }
+
+    pub async fn create_scheduled_backup_if_due(
+        pool: &SqlitePool,
+        backup_dir: &Path,
+    ) -> Result<Option<BackupInfo>, AppError> {
+        let latest = Self::list_backups(backup_dir)?.into_iter().next();
+        let is_due = match latest {
+            Some(backup) => {
+                chrono::Utc::now() - backup.created_at
+                    >= chrono::Duration::hours(BACKUP_INTERVAL_HOURS)
+            }
+            None => true,
+        };
+
+        if !is_due {
+            return Ok(None);
+        }
+
+        let backup = Self::create_backup(pool, backup_dir, BACKUP_REASON_SCHEDULED).await?;
+        let retention = Self::get_retention(pool).await;
+        Self::prune_backups(backup_dir, retention)?;
+        Ok(Some(backup))
+    }
+
+    // Newest first
+    pub fn list_backups(backup_dir: &Path) -> Result<Vec<BackupInfo>, AppError> {
+        if !backup_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups: Vec<BackupInfo> = fs::read_dir(backup_dir)
+            .map_err(|e| AppError::Io(format!("Failed to read backup dir: {e}")))?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| Self::backup_info(&entry.path()))
+            .collect();
+
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+        Ok(backups)
+    }
+
+    // Deletes the oldest backups so that at most `retention` remain
+    pub fn prune_backups(backup_dir: &Path, retention: i64) -> Result<(), AppError> {
+        let retention = retention.max(1) as usize;
+        for backup in Self::list_backups(backup_dir)?.into_iter().skip(retention) {
+            fs::remove_file(backup_dir.join(&backup.file_name)).map_err(|e| {
+                AppError::Io(format!("Failed to remove backup {}: {e}", backup.file_name))
+            })?;
+            println!("[Backup] Pruned {}", backup.file_name);
+        }
+        Ok(())
+    }
+
+    // Read straight from the table so this also works before migrations have run
+    pub async fn get_retention(pool: &SqlitePool) -> i64 {
+        sqlx::query_as::<_, (i64,)>("SELECT backup_retention FROM user_settings LIMIT 1")
+            .fetch_optional(pool)
+            .await
+            .ok()
+            .flatten()
+            .map_or(DEFAULT_BACKUP_RETENTION, |(retention,)| retention)
+    }
+
+    /**
+     * The live database can't be swapped while the pool holds it open, so a restore
+     * copies the backup next to the database and is applied on the next startup
+     * by `apply_pending_restore`, before any connection is made.
+     */
+    pub fn stage_restore(
+        backup_dir: &Path,
+        file_name: &str,
+        db_path: &Path,
+    ) -> Result<(), AppError> {
+        let is_known_backup = Self::list_backups(backup_dir)?
+            .iter()
+            .any(|backup| backup.file_name == file_name);
+        if !is_known_backup {
+            return Err(AppError::not_found("Backup", file_name));
+        }
+
+        fs::copy(
+            backup_dir.join(file_name),
+            Self::pending_restore_path(db_path),
+        )
+        .map_err(|e| AppError::Io(format!("Failed to stage backup {file_name}: {e}")))?;
+        Ok(())
+    }
+
+    pub fn apply_pending_restore(db_path: &Path) -> Result<bool, AppError> {
+        let pending = Self::pending_restore_path(db_path);
+        if !pending.exists() {
+            return Ok(false);
+        }
+
+        // Stale WAL files belong to the old database and must not be replayed onto the backup
+        for suffix in ["wal", "shm"] {
+            let sidecar = Self::sidecar_path(db_path, suffix);
+            if sidecar.exists() {
+                fs::remove_file(&sidecar).map_err(|e| {
+                    AppError::Io(format!("Failed to remove {}: {e}", sidecar.display()))
+                })?;
+            }
+        }
+
+        fs::rename(&pending, db_path)
+            .map_err(|e| AppError::Io(format!("Failed to restore backup: {e}")))?;
+        println!("[Backup] Restored database from staged backup");
+        Ok(true)
+    }
+
+    fn pending_restore_path(db_path: &Path) -> PathBuf {
+        Self::sidecar_path(db_path, PENDING_RESTORE_SUFFIX)
+    }
+
+    fn sidecar_path(db_path: &Path, suffix: &str) -> PathBuf {
+        let mut path = db_path.as_os_str().to_owned();
+        path.push(format!("-{suffix}"));
+        PathBuf::from(path)
+    }
+
+    /**
+     * Parses "oink-backup-20260101T120000000-scheduled.db" into its timestamp and reason.
+     * Files that don't follow the naming scheme are ignored.
+     */
+    fn backup_info(path: &Path) -> Option<BackupInfo> {
+        let file_name = path.file_name()?.to_str()?.to_string();
+        let stem = file_name
+            .strip_prefix(BACKUP_FILE_PREFIX)?
+            .strip_suffix(BACKUP_FILE_EXTENSION)?;
+        let (timestamp, reason) = stem.split_once('-')?;
+        let reason = reason.to_string();
+        let created_at = NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT)
+            .ok()?
+            .and_utc();
+        let size_bytes = fs::metadata(path).ok()?.len();
+
+        Some(BackupInfo {
+            file_name,
+            reason,
+            created_at,
+            size_bytes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::user_settings::UserSettingsService;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use uuid::Uuid;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("oink-backup-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    // VACUUM INTO is a no-op for in-memory databases, so these tests need a real file
+    async fn setup_file_db(dir: &Path) -> SqlitePool {
+        let db_url = format!(
+            "sqlite://{}?mode=rwc",
+            dir.join("source.db").to_string_lossy()
+        );
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&db_url)
+            .await
+            .expect("Failed to create file database");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_create_list_and_prune_backups() {
+        let dir = temp_dir();
+        let pool = setup_file_db(&dir).await;
+        UserSettingsService::upsert(&pool, "User".into(), "NZD".into(), "system".into())
+            .await
+            .expect("settings");
+
+        for _ in 0..3 {
+            BackupService::create_backup(&pool, &dir, BACKUP_REASON_MANUAL)
+                .await
+                .expect("backup");
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        let backups = BackupService::list_backups(&dir).expect("list");
+        assert_eq!(backups.len(), 3);
+        assert_eq!(backups[0].reason, BACKUP_REASON_MANUAL);
+        assert!(backups[0].size_bytes > 0);
+        assert!(backups[0].created_at >= backups[1].created_at);
+
+        BackupService::prune_backups(&dir, 2).expect("prune");
+        let remaining = BackupService::list_backups(&dir).expect("list");
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(remaining[0].file_name, backups[0].file_name);
+
+        // Already backed up within the interval
+        let scheduled = BackupService::create_scheduled_backup_if_due(&pool, &dir)
+            .await
+            .expect("scheduled");
+        assert!(scheduled.is_none());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_pre_migration_backup_skipped_when_up_to_date() {
+        let dir = temp_dir();
+        let pool = setup_file_db(&dir).await;
+
+        let backup =
+            BackupService::create_pre_migration_backup(&pool, &dir, DEFAULT_BACKUP_RETENTION)
+                .await
+                .expect("pre-migration");
+        assert!(backup.is_none());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_stage_and_apply_restore() {
+        let dir = temp_dir();
+        let pool = setup_file_db(&dir).await;
+        let backup = BackupService::create_backup(&pool, &dir, BACKUP_REASON_MANUAL)
+            .await
+            .expect("backup");
+
+        let db_path = dir.join("oink.db");
+        fs::write(&db_path, b"old").expect("write db");
+        fs::write(dir.join("oink.db-wal"), b"stale").expect("write wal");
+
+        assert!(BackupService::stage_restore(&dir, "missing.db", &db_path).is_err());
+        BackupService::stage_restore(&dir, &backup.file_name, &db_path).expect("stage");
+
+        let applied = BackupService::apply_pending_restore(&db_path).expect("apply");
+        assert!(applied);
+        assert!(!dir.join("oink.db-wal").exists());
+        assert_eq!(fs::metadata(&db_path).expect("db").len(), backup.size_bytes);
+        assert!(!BackupService::apply_pending_restore(&db_path).expect("noop"));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
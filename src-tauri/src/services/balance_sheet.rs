@@ -1,25 +1,81 @@
-use crate::models::BalanceSheet;
-use sqlx::SqlitePool;
+use crate::error::AppError;
+use crate::models::{BalanceSheet, Entry};
+use crate::services::audit::AuditService;
+use crate::services::entry::{
+    EntryProvenance, EntryService, ENTRY_SOURCE_CARRY_FORWARD, ENTRY_STATUS_CARRIED_FORWARD,
+    ENTRY_STATUS_CONFIRMED,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Sqlite, SqlitePool};
 use uuid::Uuid;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CarryForwardMode {
+    // Seed only January of the new year
+    January,
+    // Seed every month of the new year with the same balance
+    AllMonths,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CarryForwardOptions {
+    pub mode: CarryForwardMode,
+    // Flag carried values as estimated until the user confirms them
+    pub mark_estimated: bool,
+}
+
 pub struct BalanceSheetService;
 
 impl BalanceSheetService {
     // LIST
-    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<BalanceSheet>, String> {
-        sqlx::query_as::<_, BalanceSheet>("SELECT * FROM balance_sheets ORDER BY year DESC")
-            .fetch_all(pool)
-            .await
-            .map_err(|e| e.to_string())
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<BalanceSheet>, AppError> {
+        sqlx::query_as::<_, BalanceSheet>(
+            "SELECT * FROM balance_sheets WHERE deleted_at IS NULL ORDER BY year DESC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // LIST (trash)
+    pub async fn get_trashed(pool: &SqlitePool) -> Result<Vec<BalanceSheet>, AppError> {
+        sqlx::query_as::<_, BalanceSheet>(
+            "SELECT * FROM balance_sheets WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
     }
 
     // READ
-    pub async fn get_by_id(pool: &SqlitePool, id: String) -> Result<Option<BalanceSheet>, String> {
-        sqlx::query_as::<_, BalanceSheet>("SELECT * FROM balance_sheets WHERE id = ?")
-            .bind(id)
-            .fetch_optional(pool)
+    pub async fn get_by_id(
+        pool: &SqlitePool,
+        id: String,
+    ) -> Result<Option<BalanceSheet>, AppError> {
+        sqlx::query_as::<_, BalanceSheet>(
+            "SELECT * FROM balance_sheets WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // Includes a sheet in the trash, since its year is still taken
+    pub async fn get_by_year<'e, E>(
+        executor: E,
+        year: i32,
+    ) -> Result<Option<BalanceSheet>, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, BalanceSheet>("SELECT * FROM balance_sheets WHERE year = ?")
+            .bind(year)
+            .fetch_optional(executor)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
     }
 
     // UPSERT
@@ -27,28 +83,17 @@ impl BalanceSheetService {
         pool: &SqlitePool,
         id: Option<String>,
         year: i32,
-    ) -> Result<BalanceSheet, String> {
+    ) -> Result<BalanceSheet, AppError> {
         // Check if year already exists
         // This application-level check is useful for fast feedback, but the DB constraint is the authority.
-        let existing_year =
-            sqlx::query_as::<_, BalanceSheet>("SELECT * FROM balance_sheets WHERE year = ?")
-                .bind(year)
-                .fetch_optional(pool)
-                .await
-                .map_err(|e| e.to_string())?;
+        let existing_year = Self::get_by_year(pool, year).await?;
 
         if let Some(existing) = existing_year {
-            if let Some(ref uid) = id {
-                if existing.id != *uid {
-                    return Err(format!("Balance sheet for year {year} already exists"));
-                }
-            } else {
-                return Err(format!("Balance sheet for year {year} already exists"));
+            if id.as_ref() != Some(&existing.id) {
+                return Err(Self::existing_year_conflict(&existing));
             }
         }
 
-        let now = chrono::Utc::now();
-
         if let Some(uid) = id {
             let exists: Option<BalanceSheet> = Self::get_by_id(pool, uid.clone()).await?;
             if exists.is_some() {
@@ -61,42 +106,180 @@ impl BalanceSheetService {
                 .bind(uid)
                 .fetch_one(pool)
                 .await
-                .map_err(|e| Self::handle_db_error(&e, year));
+                .map_err(|e| Self::handle_db_error(e, year));
+            }
+        }
+
+        Self::create_with_executor(pool, year).await
+    }
+
+    /**
+     * Creates the sheet for `year` and, in the same transaction, copies each non-archived
+     * account's closing balance from the most recent earlier sheet into the new year.
+     * The closing balance is the account's December entry, or its latest month when
+     * December wasn't filled in.
+     */
+    pub async fn create_with_carry_forward(
+        pool: &SqlitePool,
+        year: i32,
+        options: CarryForwardOptions,
+    ) -> Result<BalanceSheet, AppError> {
+        if let Some(existing) = Self::get_by_year(pool, year).await? {
+            return Err(Self::existing_year_conflict(&existing));
+        }
+
+        let mut tx = pool.begin().await?;
+        let sheet = Self::create_with_executor(&mut *tx, year).await?;
+
+        let previous = sqlx::query_as::<_, BalanceSheet>(
+            "SELECT * FROM balance_sheets WHERE year < ? AND deleted_at IS NULL ORDER BY year DESC LIMIT 1",
+        )
+        .bind(year)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(previous) = previous {
+            let closing_balances: Vec<(String, f64)> = sqlx::query_as(
+                "SELECT e.account_id, e.amount FROM entries e
+                 JOIN accounts a ON a.id = e.account_id
+                 WHERE e.balance_sheet_id = $1 AND a.is_archived = 0 AND a.deleted_at IS NULL
+                 AND e.month = (SELECT MAX(month) FROM entries WHERE balance_sheet_id = $1 AND account_id = e.account_id)",
+            )
+            .bind(&previous.id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let status = if options.mark_estimated {
+                ENTRY_STATUS_CARRIED_FORWARD
+            } else {
+                ENTRY_STATUS_CONFIRMED
+            };
+            let provenance = EntryProvenance::new(status, Some(ENTRY_SOURCE_CARRY_FORWARD));
+            let months = match options.mode {
+                CarryForwardMode::January => 1..=1,
+                CarryForwardMode::AllMonths => 1..=12,
+            };
+            for (account_id, amount) in &closing_balances {
+                for month in months.clone() {
+                    let entry = EntryService::create_with_executor(
+                        &mut *tx,
+                        &sheet.id,
+                        account_id,
+                        month,
+                        *amount,
+                        &provenance,
+                    )
+                    .await?;
+                    AuditService::record_entry(&mut tx, None, Some(&entry)).await?;
+                }
             }
         }
 
+        tx.commit().await?;
+        Ok(sheet)
+    }
+
+    pub async fn create_with_executor<'e, E>(
+        executor: E,
+        year: i32,
+    ) -> Result<BalanceSheet, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let new_id = Uuid::new_v4().to_string();
+        let now = chrono::Utc::now();
         sqlx::query_as::<_, BalanceSheet>(
             "INSERT INTO balance_sheets (id, year, created_at) VALUES (?, ?, ?) RETURNING *",
         )
         .bind(new_id)
         .bind(year)
         .bind(now)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
-        .map_err(|e| Self::handle_db_error(&e, year))
+        .map_err(|e| Self::handle_db_error(e, year))
     }
 
-    // DELETE
-    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), String> {
-        sqlx::query("DELETE FROM balance_sheets WHERE id = ?")
+    // DELETE (moves the sheet to the trash; its entries stay put until it's purged)
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        sqlx::query("UPDATE balance_sheets SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL")
+            .bind(chrono::Utc::now())
             .bind(id)
             .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
+        Ok(())
+    }
+
+    // RESTORE (from the trash)
+    pub async fn restore(pool: &SqlitePool, id: String) -> Result<BalanceSheet, AppError> {
+        sqlx::query_as::<_, BalanceSheet>(
+            "UPDATE balance_sheets SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL RETURNING *",
+        )
+        .bind(&id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("BalanceSheet", id))
+    }
+
+    // PURGE (permanently deletes a trashed sheet; entries cascade, so they're logged as deleted too)
+    pub async fn purge(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let in_trash: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM balance_sheets WHERE id = ? AND deleted_at IS NOT NULL")
+                .bind(&id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if in_trash.is_none() {
+            return Err(AppError::not_found("BalanceSheet", id));
+        }
+
+        let entries =
+            sqlx::query_as::<_, Entry>("SELECT * FROM entries WHERE balance_sheet_id = ?")
+                .bind(&id)
+                .fetch_all(&mut *tx)
+                .await?;
+        for entry in &entries {
+            AuditService::record_entry(&mut tx, Some(entry), None).await?;
+        }
+
+        sqlx::query("DELETE FROM balance_sheets WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
         Ok(())
     }
 
-    fn handle_db_error(e: &sqlx::Error, year: i32) -> String {
+    fn handle_db_error(e: sqlx::Error, year: i32) -> AppError {
         if let sqlx::Error::Database(db_err) = &e {
             // Check for unique constraint violation.
             // SQLite error code 2067 is SQLITE_CONSTRAINT_UNIQUE
             // sqlx 0.8 might expose .is_unique_violation() or similar
             if db_err.is_unique_violation() {
-                return format!("Balance sheet for year {year} already exists");
+                return Self::year_conflict(year);
             }
         }
-        e.to_string()
+        AppError::from(e)
+    }
+
+    fn existing_year_conflict(existing: &BalanceSheet) -> AppError {
+        if existing.deleted_at.is_some() {
+            return AppError::conflict(
+                "BalanceSheet",
+                format!(
+                    "Balance sheet for year {} is in the trash. Restore or purge it first",
+                    existing.year
+                ),
+            );
+        }
+        Self::year_conflict(existing.year)
+    }
+
+    fn year_conflict(year: i32) -> AppError {
+        AppError::conflict(
+            "BalanceSheet",
+            format!("Balance sheet for year {year} already exists"),
+        )
     }
 }
 
@@ -137,6 +320,139 @@ mod tests {
         assert!(check.is_none());
     }
 
+    #[tokio::test]
+    async fn test_create_with_carry_forward() {
+        use crate::services::account::AccountService;
+
+        let pool = setup_test_db().await;
+        let previous = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("2024");
+        let checking = AccountService::upsert(
+            &pool,
+            None,
+            "Checking".into(),
+            "Asset".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("checking");
+        let loan = AccountService::upsert(
+            &pool,
+            None,
+            "Loan".into(),
+            "Liability".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("loan");
+        let closed = AccountService::upsert(
+            &pool,
+            None,
+            "Closed".into(),
+            "Asset".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("closed");
+        AccountService::toggle_archive(&pool, closed.id.clone())
+            .await
+            .expect("archive");
+
+        EntryService::upsert(
+            &pool,
+            previous.id.clone(),
+            checking.id.clone(),
+            11,
+            900.0,
+            Default::default(),
+        )
+        .await
+        .expect("nov");
+        EntryService::upsert(
+            &pool,
+            previous.id.clone(),
+            checking.id.clone(),
+            12,
+            1000.0,
+            Default::default(),
+        )
+        .await
+        .expect("dec");
+        // No December entry, so October is the closing balance
+        EntryService::upsert(
+            &pool,
+            previous.id.clone(),
+            loan.id.clone(),
+            10,
+            5000.0,
+            Default::default(),
+        )
+        .await
+        .expect("loan oct");
+        EntryService::upsert(&pool, previous.id, closed.id, 12, 50.0, Default::default())
+            .await
+            .expect("closed dec");
+
+        let sheet = BalanceSheetService::create_with_carry_forward(
+            &pool,
+            2025,
+            CarryForwardOptions {
+                mode: CarryForwardMode::January,
+                mark_estimated: true,
+            },
+        )
+        .await
+        .expect("carry forward");
+
+        let mut entries = EntryService::get_by_balance_sheet(&pool, sheet.id.clone(), None)
+            .await
+            .expect("entries");
+        entries.sort_by(|a, b| a.amount.total_cmp(&b.amount));
+        assert_eq!(entries.len(), 2);
+        assert!(entries
+            .iter()
+            .all(|e| e.month == 1 && e.status == ENTRY_STATUS_CARRIED_FORWARD));
+        assert_eq!(entries[0].amount, 1000.0);
+        assert_eq!(entries[1].amount, 5000.0);
+
+        let confirmed = EntryService::confirm_estimated(&pool, sheet.id.clone(), Some(1))
+            .await
+            .expect("confirm");
+        assert_eq!(confirmed, 2);
+
+        let all_months = BalanceSheetService::create_with_carry_forward(
+            &pool,
+            2026,
+            CarryForwardOptions {
+                mode: CarryForwardMode::AllMonths,
+                mark_estimated: false,
+            },
+        )
+        .await
+        .expect("all months");
+        let entries = EntryService::get_by_balance_sheet(&pool, all_months.id, None)
+            .await
+            .expect("entries");
+        // Carried from 2025, whose latest month is January
+        assert_eq!(entries.len(), 24);
+        assert!(entries.iter().all(|e| e.status == ENTRY_STATUS_CONFIRMED));
+
+        let duplicate = BalanceSheetService::create_with_carry_forward(
+            &pool,
+            2026,
+            CarryForwardOptions {
+                mode: CarryForwardMode::January,
+                mark_estimated: false,
+            },
+        )
+        .await;
+        assert!(matches!(duplicate, Err(AppError::Conflict { .. })));
+    }
+
     #[tokio::test]
     async fn test_duplicate_year_race_condition() {
         let pool = setup_test_db().await;
@@ -155,7 +471,7 @@ mod tests {
         let result = BalanceSheetService::upsert(&pool, None, 2025).await;
         assert!(result.is_err());
         assert_eq!(
-            result.unwrap_err(),
+            result.unwrap_err().to_string(),
             "Balance sheet for year 2025 already exists"
         );
 
@@ -0,0 +1,538 @@
+use crate::error::AppError;
+use crate::models::{Budget, BudgetCategory};
+use crate::services::currency_rates::conversion::CurrencyConverter;
+use crate::services::user_settings::UserSettingsService;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Sqlite, SqlitePool};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetLine {
+    pub category_id: String,
+    pub category_name: String,
+    pub budgeted: f64,
+    pub rolled_over: f64,
+    // budgeted + rolled_over
+    pub available: f64,
+    // Net spending from transactions tagged with this category (refunds reduce it)
+    pub actual: f64,
+    // available - actual; negative when overspent
+    pub remaining: f64,
+}
+
+/**
+ * Budget vs actual for a single month, in the user's home currency.
+ * Transactions whose account currency can't be converted for that month are left out
+ * of `actual` and their currencies listed in `missing_currencies`.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetReport {
+    pub year: i32,
+    pub month: u32,
+    pub currency: String,
+    pub lines: Vec<BudgetLine>,
+    pub missing_currencies: Vec<String>,
+}
+
+pub struct BudgetService;
+
+impl BudgetService {
+    // LIST categories
+    pub async fn get_categories(pool: &SqlitePool) -> Result<Vec<BudgetCategory>, AppError> {
+        sqlx::query_as::<_, BudgetCategory>(
+            "SELECT * FROM budget_categories ORDER BY name COLLATE NOCASE ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // UPSERT category
+    pub async fn upsert_category(
+        pool: &SqlitePool,
+        id: Option<String>,
+        name: String,
+        rollover: bool,
+    ) -> Result<BudgetCategory, AppError> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::validation("name", "Category name is required"));
+        }
+
+        let duplicate_name = |e: sqlx::Error| match &e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => AppError::conflict(
+                "BudgetCategory",
+                format!("A budget category named '{name}' already exists"),
+            ),
+            _ => AppError::from(e),
+        };
+
+        if let Some(uid) = id {
+            let mut tx = pool.begin().await?;
+            let previous: Option<String> =
+                sqlx::query_scalar("SELECT name FROM budget_categories WHERE id = ?")
+                    .bind(&uid)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            let Some(previous) = previous else {
+                return Err(AppError::not_found("BudgetCategory", uid));
+            };
+            let category = sqlx::query_as::<_, BudgetCategory>(
+                "UPDATE budget_categories SET name = ?, rollover = ? WHERE id = ? RETURNING *",
+            )
+            .bind(&name)
+            .bind(rollover)
+            .bind(&uid)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(duplicate_name)?;
+            // Spending is matched to categories by name, so it follows the rename
+            sqlx::query("UPDATE transactions SET category = ? WHERE LOWER(category) = LOWER(?)")
+                .bind(&name)
+                .bind(&previous)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            return Ok(category);
+        }
+
+        sqlx::query_as::<_, BudgetCategory>(
+            "INSERT INTO budget_categories (id, name, rollover, created_at) VALUES (?, ?, ?, ?) RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&name)
+        .bind(rollover)
+        .bind(chrono::Utc::now())
+        .fetch_one(pool)
+        .await
+        .map_err(duplicate_name)
+    }
+
+    // DELETE category (and its budgets)
+    pub async fn delete_category(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM budget_categories WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // LIST budgets for a month
+    pub async fn get_budgets(
+        pool: &SqlitePool,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<Budget>, AppError> {
+        sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE year = ? AND month = ?")
+            .bind(year)
+            .bind(month)
+            .fetch_all(pool)
+            .await
+            .map_err(AppError::from)
+    }
+
+    // UPSERT (business key: category + year + month). Keeps any rolled over amount.
+    pub async fn set_budget(
+        pool: &SqlitePool,
+        category_id: String,
+        year: i32,
+        month: u32,
+        amount: f64,
+    ) -> Result<Budget, AppError> {
+        if !(1..=12).contains(&month) {
+            return Err(AppError::validation(
+                "month",
+                format!("Invalid month: {month}"),
+            ));
+        }
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(AppError::validation(
+                "amount",
+                format!("Invalid budget amount: {amount}"),
+            ));
+        }
+
+        let category_exists: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM budget_categories WHERE id = ?")
+                .bind(&category_id)
+                .fetch_optional(pool)
+                .await?;
+        if category_exists.is_none() {
+            return Err(AppError::not_found("BudgetCategory", category_id));
+        }
+
+        Self::upsert_with_executor(pool, &category_id, year, month, amount, None).await
+    }
+
+    // `rollover_amount` of None leaves an existing carry-over untouched
+    async fn upsert_with_executor<'e, E>(
+        executor: E,
+        category_id: &str,
+        year: i32,
+        month: u32,
+        amount: f64,
+        rollover_amount: Option<f64>,
+    ) -> Result<Budget, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, Budget>(
+            "INSERT INTO budgets (id, category_id, year, month, amount, rollover_amount, updated_at)
+             VALUES ($1, $2, $3, $4, $5, COALESCE($6, 0), $7)
+             ON CONFLICT (category_id, year, month) DO UPDATE SET
+                amount = CASE WHEN $6 IS NULL THEN excluded.amount ELSE budgets.amount END,
+                rollover_amount = COALESCE($6, budgets.rollover_amount),
+                updated_at = excluded.updated_at
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(category_id)
+        .bind(year)
+        .bind(month)
+        .bind(amount)
+        .bind(rollover_amount)
+        .bind(chrono::Utc::now())
+        .fetch_one(executor)
+        .await
+        .map_err(AppError::from)
+    }
+
+    /**
+     * Carries each rollover category's unspent amount from `year`/`month` into the next month.
+     * Overspending is not carried. Next month's budget is created with the same amount if it
+     * doesn't exist yet; an existing one keeps its amount. Running it again recomputes the carry.
+     */
+    pub async fn rollover(
+        pool: &SqlitePool,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<Budget>, AppError> {
+        let report = Self::get_report(pool, year, month).await?;
+        let rollover_ids: BTreeSet<String> = Self::get_categories(pool)
+            .await?
+            .into_iter()
+            .filter(|c| c.rollover)
+            .map(|c| c.id)
+            .collect();
+        let (next_year, next_month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+
+        let mut tx = pool.begin().await?;
+        let mut budgets = Vec::new();
+        for line in report
+            .lines
+            .iter()
+            .filter(|line| rollover_ids.contains(&line.category_id))
+        {
+            let budget = Self::upsert_with_executor(
+                &mut *tx,
+                &line.category_id,
+                next_year,
+                next_month,
+                line.budgeted,
+                Some(line.remaining.max(0.0)),
+            )
+            .await?;
+            budgets.push(budget);
+        }
+        tx.commit().await?;
+
+        Ok(budgets)
+    }
+
+    /**
+     * Budget vs actual for every category that has a budget or spending in the month.
+     * Actuals come from non-transfer transactions whose category matches a budget category name.
+     */
+    pub async fn get_report(
+        pool: &SqlitePool,
+        year: i32,
+        month: u32,
+    ) -> Result<BudgetReport, AppError> {
+        let (Some(start), Some(end)) = (
+            NaiveDate::from_ymd_opt(year, month, 1),
+            if month == 12 {
+                NaiveDate::from_ymd_opt(year + 1, 1, 1)
+            } else {
+                NaiveDate::from_ymd_opt(year, month + 1, 1)
+            },
+        ) else {
+            return Err(AppError::validation(
+                "month",
+                format!("Invalid month: {year}-{month}"),
+            ));
+        };
+
+        let home_currency = UserSettingsService::get_all(pool)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::missing("User settings"))?
+            .home_currency;
+        let converter = CurrencyConverter::load(pool).await?;
+
+        let rows: Vec<(String, f64, String)> = sqlx::query_as(
+            "SELECT t.category, t.amount, a.currency FROM transactions t JOIN accounts a ON a.id = t.account_id WHERE t.date >= ? AND t.date < ? AND t.transfer_id IS NULL AND t.category IS NOT NULL AND a.deleted_at IS NULL",
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+        // Lowercased category name -> spending in home currency
+        let mut actuals: HashMap<String, f64> = HashMap::new();
+        let mut missing_currencies = BTreeSet::new();
+        for (category, amount, currency) in rows {
+            let Some(conversion) = converter.convert_rate(&currency, &home_currency, year, month)
+            else {
+                missing_currencies.insert(currency);
+                continue;
+            };
+            // Outflows are negative in the ledger, spending is positive here
+            *actuals.entry(category.trim().to_lowercase()).or_default() -= amount * conversion.rate;
+        }
+
+        let budgets: HashMap<String, Budget> = Self::get_budgets(pool, year, month)
+            .await?
+            .into_iter()
+            .map(|b| (b.category_id.clone(), b))
+            .collect();
+
+        let lines = Self::get_categories(pool)
+            .await?
+            .into_iter()
+            .filter_map(|category| {
+                let budget = budgets.get(&category.id);
+                let actual = actuals
+                    .get(&category.name.to_lowercase())
+                    .copied()
+                    .unwrap_or(0.0);
+                if budget.is_none() && actual == 0.0 {
+                    return None;
+                }
+
+                let budgeted = budget.map_or(0.0, |b| b.amount);
+                let rolled_over = budget.map_or(0.0, |b| b.rollover_amount);
+                let available = budgeted + rolled_over;
+                Some(BudgetLine {
+                    category_id: category.id,
+                    category_name: category.name,
+                    budgeted,
+                    rolled_over,
+                    available,
+                    actual,
+                    remaining: available - actual,
+                })
+            })
+            .collect();
+
+        Ok(BudgetReport {
+            year,
+            month,
+            currency: home_currency,
+            lines,
+            missing_currencies: missing_currencies.into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::account::AccountService;
+    use crate::services::currency_rates::currency_rate::{CurrencyRateService, MANUAL_PROVIDER};
+    use crate::services::transaction::{TransactionInput, TransactionService, TransferInput};
+    use crate::test_utils::setup_test_db;
+
+    async fn spend(pool: &SqlitePool, account_id: &str, day: u32, amount: f64, category: &str) {
+        TransactionService::upsert(
+            pool,
+            None,
+            TransactionInput {
+                account_id: account_id.into(),
+                date: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+                amount,
+                payee: None,
+                category: Some(category.into()),
+                memo: None,
+            },
+        )
+        .await
+        .expect("transaction");
+    }
+
+    #[tokio::test]
+    async fn test_budget_report_converts_to_home_currency() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "NZD".into(), "system".into())
+            .await
+            .expect("settings");
+        let nzd = AccountService::upsert(
+            &pool,
+            None,
+            "Checking".into(),
+            "Asset".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("nzd account")
+        .id;
+        let usd = AccountService::upsert(
+            &pool,
+            None,
+            "Card".into(),
+            "Liability".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("usd account")
+        .id;
+        let eur = AccountService::upsert(
+            &pool,
+            None,
+            "Travel".into(),
+            "Asset".into(),
+            "EUR".into(),
+            None,
+        )
+        .await
+        .expect("eur account")
+        .id;
+        CurrencyRateService::upsert(
+            &pool,
+            None,
+            "USD".into(),
+            "NZD".into(),
+            MANUAL_PROVIDER.into(),
+            1.5,
+            3,
+            2025,
+        )
+        .await
+        .expect("rate");
+
+        let groceries = BudgetService::upsert_category(&pool, None, "Groceries".into(), false)
+            .await
+            .expect("category");
+        let duplicate =
+            BudgetService::upsert_category(&pool, None, " groceries ".into(), false).await;
+        assert!(matches!(duplicate, Err(AppError::Conflict { .. })));
+
+        BudgetService::set_budget(&pool, groceries.id.clone(), 2025, 3, 400.0)
+            .await
+            .expect("budget");
+        // Setting it again updates in place
+        BudgetService::set_budget(&pool, groceries.id.clone(), 2025, 3, 500.0)
+            .await
+            .expect("budget update");
+
+        spend(&pool, &nzd, 2, -120.0, "Groceries").await;
+        spend(&pool, &usd, 9, -100.0, "groceries").await;
+        spend(&pool, &nzd, 15, 20.0, "Groceries").await;
+        spend(&pool, &eur, 20, -50.0, "Groceries").await;
+        TransactionService::create_transfer(
+            &pool,
+            TransferInput {
+                from_account_id: nzd.clone(),
+                to_account_id: usd.clone(),
+                date: NaiveDate::from_ymd_opt(2025, 3, 25).unwrap(),
+                amount: 300.0,
+                to_amount: Some(180.0),
+                memo: None,
+            },
+        )
+        .await
+        .expect("transfer");
+
+        let report = BudgetService::get_report(&pool, 2025, 3)
+            .await
+            .expect("report");
+        assert_eq!(report.currency, "NZD");
+        assert_eq!(report.missing_currencies, vec!["EUR"]);
+        assert_eq!(report.lines.len(), 1);
+        let line = &report.lines[0];
+        assert_eq!(line.budgeted, 500.0);
+        // 120 + 100 USD * 1.5 - 20 refund
+        assert!((line.actual - 250.0).abs() < 1e-9);
+        assert!((line.remaining - 250.0).abs() < 1e-9);
+
+        // Renaming the category keeps its spending
+        BudgetService::upsert_category(&pool, Some(groceries.id), "Food".into(), false)
+            .await
+            .expect("rename");
+        let report = BudgetService::get_report(&pool, 2025, 3)
+            .await
+            .expect("report after rename");
+        assert_eq!(report.lines[0].category_name, "Food");
+        assert!((report.lines[0].actual - 250.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_carries_unspent_amount() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "NZD".into(), "system".into())
+            .await
+            .expect("settings");
+        let account = AccountService::upsert(
+            &pool,
+            None,
+            "Checking".into(),
+            "Asset".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("account")
+        .id;
+
+        let dining = BudgetService::upsert_category(&pool, None, "Dining".into(), true)
+            .await
+            .expect("dining");
+        let fuel = BudgetService::upsert_category(&pool, None, "Fuel".into(), false)
+            .await
+            .expect("fuel");
+        BudgetService::set_budget(&pool, dining.id.clone(), 2025, 3, 200.0)
+            .await
+            .expect("dining budget");
+        BudgetService::set_budget(&pool, fuel.id.clone(), 2025, 3, 100.0)
+            .await
+            .expect("fuel budget");
+        BudgetService::set_budget(&pool, dining.id.clone(), 2025, 4, 150.0)
+            .await
+            .expect("april dining budget");
+        spend(&pool, &account, 10, -80.0, "Dining").await;
+        spend(&pool, &account, 11, -40.0, "Fuel").await;
+
+        let rolled = BudgetService::rollover(&pool, 2025, 3)
+            .await
+            .expect("rollover");
+        assert_eq!(rolled.len(), 1);
+        assert_eq!(rolled[0].category_id, dining.id);
+        assert_eq!((rolled[0].year, rolled[0].month), (2025, 4));
+        assert_eq!(rolled[0].amount, 150.0);
+        assert_eq!(rolled[0].rollover_amount, 120.0);
+
+        // Rerunning after more spending recomputes rather than stacking the carry
+        spend(&pool, &account, 12, -100.0, "Dining").await;
+        let rolled = BudgetService::rollover(&pool, 2025, 3)
+            .await
+            .expect("rerun");
+        assert_eq!(rolled[0].rollover_amount, 20.0);
+
+        // Changing the amount later keeps the carry
+        let april = BudgetService::set_budget(&pool, dining.id, 2025, 4, 175.0)
+            .await
+            .expect("april update");
+        assert_eq!(april.rollover_amount, 20.0);
+        assert_eq!(april.amount, 175.0);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::AppError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::Sqlite;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    Created,
+    Updated,
+    Skipped,
+    Error,
+}
+
+/**
+ * Outcome of one CSV line. The parsed row's fields are inlined, and left out when the
+ * line couldn't be parsed at all.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowResult<R> {
+    pub line: u64,
+    #[serde(flatten)]
+    pub row: Option<R>,
+    pub status: ImportRowStatus,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport<R> {
+    pub dry_run: bool,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub errored: usize,
+    pub rows: Vec<ImportRowResult<R>>,
+}
+
+// A CSV row type an importer reads
+pub trait CsvRow: DeserializeOwned + Clone {
+    // Normalises the parsed values and rejects the ones the importer can't store
+    fn validate(&mut self) -> Result<(), AppError>;
+}
+
+impl<R: CsvRow> ImportReport<R> {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            created: 0,
+            updated: 0,
+            skipped: 0,
+            errored: 0,
+            rows: Vec::new(),
+        }
+    }
+
+    /**
+     * Parses `contents` as CSV with headers matched case-insensitively.
+     * Malformed and invalid lines are reported as errors; the rest are returned with their line.
+     */
+    pub fn read_rows(&mut self, contents: &str) -> Result<Vec<(u64, R)>, AppError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .flexible(true)
+            .from_reader(contents.as_bytes());
+
+        let headers = reader
+            .headers()
+            .map_err(|e| AppError::invalid(format!("Failed to read CSV headers: {e}")))?
+            .iter()
+            .map(|h| h.to_lowercase())
+            .collect::<csv::StringRecord>();
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let (line, row) = match record {
+                Ok(record) => {
+                    let line = record.position().map_or(0, |p| p.line());
+                    (line, record.deserialize::<R>(Some(&headers)))
+                }
+                Err(e) => {
+                    let line = e.position().map_or(0, |p| p.line());
+                    self.push_error(line, None, format!("Malformed row: {e}"));
+                    continue;
+                }
+            };
+
+            let mut row = match row {
+                Ok(row) => row,
+                Err(e) => {
+                    self.push_error(line, None, format!("Invalid row: {e}"));
+                    continue;
+                }
+            };
+
+            match row.validate() {
+                Ok(()) => rows.push((line, row)),
+                Err(error) => self.push_error(line, Some(&row), error.to_string()),
+            }
+        }
+        Ok(rows)
+    }
+
+    pub fn push(
+        &mut self,
+        line: u64,
+        row: Option<&R>,
+        status: ImportRowStatus,
+        message: Option<String>,
+    ) {
+        match status {
+            ImportRowStatus::Created => self.created += 1,
+            ImportRowStatus::Updated => self.updated += 1,
+            ImportRowStatus::Skipped => self.skipped += 1,
+            ImportRowStatus::Error => self.errored += 1,
+        }
+
+        self.rows.push(ImportRowResult {
+            line,
+            row: row.cloned(),
+            status,
+            message,
+        });
+    }
+
+    pub fn push_error(&mut self, line: u64, row: Option<&R>, message: String) {
+        self.push(line, row, ImportRowStatus::Error, Some(message));
+    }
+
+    /**
+     * Keeps the import's writes, or rolls them back when it was only a dry run.
+     * Rows rejected while reading are reported first, so the rows are put back in file order.
+     */
+    pub async fn finish(&mut self, tx: sqlx::Transaction<'_, Sqlite>) -> Result<(), AppError> {
+        self.rows.sort_by_key(|row| row.line);
+        if self.dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use crate::error::AppError;
+use crate::models::CurrencyRate;
+use crate::services::currency_rates::currency_rate::CurrencyRateService;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// (Year, Month) -> Rate, for a single ordered currency pair
+type PairRates = BTreeMap<(i32, u32), f64>;
+
+/**
+ * A resolved rate between two currencies for a given month.
+ * `year`/`month` is the period of the oldest rate used along `path`,
+ * so a conversion is exact only when it matches the requested period.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionRate {
+    pub rate: f64,
+    pub year: i32,
+    pub month: u32,
+    pub path: Vec<String>,
+}
+
+impl ConversionRate {
+    pub fn is_exact(&self, year: i32, month: u32) -> bool {
+        (self.year, self.month) == (year, month)
+    }
+}
+
+/**
+ * Converts between any two currencies present in `currency_rates`.
+ * Each stored rate is usable in both directions, and conversions can triangulate
+ * through intermediate currencies (e.g. NZD -> USD -> EUR). For a given month the
+ * shortest path wins; among equally short paths, the one whose oldest rate is most recent.
+ * Rates are never taken from after the requested month.
+ */
+pub struct CurrencyConverter {
+    // From -> To -> rates by period
+    edges: HashMap<String, HashMap<String, PairRates>>,
+}
+
+impl CurrencyConverter {
+    pub async fn load(pool: &SqlitePool) -> Result<Self, AppError> {
+        let rates = CurrencyRateService::get_all(pool).await?;
+        Ok(Self::new(&rates))
+    }
+
+    pub fn new(rates: &[CurrencyRate]) -> Self {
+        let mut edges: HashMap<String, HashMap<String, PairRates>> = HashMap::new();
+
+        // Inverses first so a stored direct rate always wins for the same period
+        for r in rates.iter().filter(|r| r.rate > 0.0) {
+            edges
+                .entry(r.to_currency.clone())
+                .or_default()
+                .entry(r.from_currency.clone())
+                .or_default()
+                .insert((r.year, r.month), 1.0 / r.rate);
+        }
+        for r in rates.iter().filter(|r| r.rate > 0.0) {
+            edges
+                .entry(r.from_currency.clone())
+                .or_default()
+                .entry(r.to_currency.clone())
+                .or_default()
+                .insert((r.year, r.month), r.rate);
+        }
+
+        Self { edges }
+    }
+
+    /**
+     * Returns the rate to multiply an amount in `from` by to get `to`,
+     * or None when no chain of known rates links the two currencies by that month.
+     */
+    pub fn convert_rate(
+        &self,
+        from: &str,
+        to: &str,
+        year: i32,
+        month: u32,
+    ) -> Option<ConversionRate> {
+        if from == to {
+            return Some(ConversionRate {
+                rate: 1.0,
+                year,
+                month,
+                path: vec![from.to_string()],
+            });
+        }
+
+        // Layered BFS: every node keeps the freshest path found at its shortest depth
+        let mut visited: HashSet<&str> = HashSet::from([from]);
+        let mut frontier: BTreeMap<&str, ConversionRate> = BTreeMap::from([(
+            from,
+            ConversionRate {
+                rate: 1.0,
+                year,
+                month,
+                path: vec![from.to_string()],
+            },
+        )]);
+
+        while !frontier.is_empty() {
+            let mut next: BTreeMap<&str, ConversionRate> = BTreeMap::new();
+
+            for (node, reached) in &frontier {
+                let Some(neighbours) = self.edges.get(*node) else {
+                    continue;
+                };
+                for (neighbour, pair_rates) in neighbours {
+                    if visited.contains(neighbour.as_str()) {
+                        continue;
+                    }
+                    let Some((&period, &rate)) = pair_rates.range(..=(year, month)).next_back()
+                    else {
+                        continue;
+                    };
+
+                    let oldest = period.min((reached.year, reached.month));
+                    let mut path = reached.path.clone();
+                    path.push(neighbour.clone());
+                    let candidate = ConversionRate {
+                        rate: reached.rate * rate,
+                        year: oldest.0,
+                        month: oldest.1,
+                        path,
+                    };
+
+                    let is_fresher = next.get(neighbour.as_str()).is_none_or(|current| {
+                        (candidate.year, candidate.month) > (current.year, current.month)
+                    });
+                    if is_fresher {
+                        next.insert(neighbour.as_str(), candidate);
+                    }
+                }
+            }
+
+            if let Some(found) = next.remove(to) {
+                return Some(found);
+            }
+
+            visited.extend(next.keys().copied());
+            frontier = next;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn rate(from: &str, to: &str, rate: f64, year: i32, month: u32) -> CurrencyRate {
+        CurrencyRate {
+            id: format!("{from}-{to}-{year}-{month}"),
+            from_currency: from.into(),
+            to_currency: to.into(),
+            provider: "manual".into(),
+            rate,
+            month,
+            year,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn convert_rate_uses_direct_and_inverse_pairs() {
+        let converter = CurrencyConverter::new(&[rate("USD", "NZD", 1.6, 2025, 1)]);
+
+        let direct = converter
+            .convert_rate("USD", "NZD", 2025, 1)
+            .expect("direct");
+        assert!((direct.rate - 1.6).abs() < 1e-9);
+        assert!(direct.is_exact(2025, 1));
+
+        let inverse = converter
+            .convert_rate("NZD", "USD", 2025, 3)
+            .expect("inverse");
+        assert!((inverse.rate - 1.0 / 1.6).abs() < 1e-9);
+        assert!(!inverse.is_exact(2025, 3));
+
+        assert!(converter.convert_rate("USD", "NZD", 2024, 12).is_none());
+    }
+
+    #[test]
+    fn convert_rate_triangulates_through_intermediate_currency() {
+        let converter = CurrencyConverter::new(&[
+            rate("EUR", "USD", 1.1, 2025, 1),
+            rate("NZD", "USD", 0.6, 2025, 1),
+        ]);
+
+        // EUR -> USD -> NZD
+        let conversion = converter
+            .convert_rate("EUR", "NZD", 2025, 1)
+            .expect("triangulated");
+        assert_eq!(conversion.path, vec!["EUR", "USD", "NZD"]);
+        assert!((conversion.rate - 1.1 / 0.6).abs() < 1e-9);
+
+        assert!(converter.convert_rate("EUR", "JPY", 2025, 1).is_none());
+    }
+
+    #[test]
+    fn convert_rate_prefers_shortest_then_most_recent_path() {
+        let converter = CurrencyConverter::new(&[
+            // Stale direct pair still beats a fresher two-hop path
+            rate("EUR", "NZD", 1.7, 2024, 6),
+            rate("EUR", "USD", 1.1, 2025, 1),
+            rate("USD", "NZD", 1.6, 2025, 1),
+            // Two equally short paths to JPY: via GBP is fresher than via AUD
+            rate("EUR", "GBP", 0.85, 2025, 1),
+            rate("GBP", "JPY", 190.0, 2025, 1),
+            rate("EUR", "AUD", 1.6, 2024, 1),
+            rate("AUD", "JPY", 95.0, 2025, 1),
+        ]);
+
+        let nzd = converter.convert_rate("EUR", "NZD", 2025, 1).expect("nzd");
+        assert_eq!(nzd.path, vec!["EUR", "NZD"]);
+        assert_eq!((nzd.year, nzd.month), (2024, 6));
+
+        let jpy = converter.convert_rate("EUR", "JPY", 2025, 1).expect("jpy");
+        assert_eq!(jpy.path, vec!["EUR", "GBP", "JPY"]);
+        assert!(jpy.is_exact(2025, 1));
+    }
+}
@@ -1,18 +1,23 @@
+use crate::error::AppError;
 use crate::models::CurrencyRate;
-use sqlx::SqlitePool;
+use crate::services::audit::{AuditService, AUDIT_ENTITY_CURRENCY_RATE};
+use sqlx::{Executor, Sqlite, SqlitePool};
 use uuid::Uuid;
 
+// Rates entered by the user; provider syncs never overwrite these
+pub const MANUAL_PROVIDER: &str = "manual";
+
 pub struct CurrencyRateService;
 
 impl CurrencyRateService {
     // LIST
-    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<CurrencyRate>, String> {
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<CurrencyRate>, AppError> {
         sqlx::query_as::<_, CurrencyRate>(
             "SELECT * FROM currency_rates ORDER BY year DESC, month ASC",
         )
         .fetch_all(pool)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
     }
 
     // LIST BY YEAR & MONTH
@@ -21,7 +26,7 @@ impl CurrencyRateService {
         pool: &SqlitePool,
         year: i32,
         month: i32,
-    ) -> Result<Vec<CurrencyRate>, String> {
+    ) -> Result<Vec<CurrencyRate>, AppError> {
         sqlx::query_as::<_, CurrencyRate>(
             "SELECT * FROM currency_rates WHERE year = ? AND month = ?",
         )
@@ -29,22 +34,46 @@ impl CurrencyRateService {
         .bind(month)
         .fetch_all(pool)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
     }
 
     // READ
-    pub async fn get_by_id(pool: &SqlitePool, id: String) -> Result<Option<CurrencyRate>, String> {
+    pub async fn get_by_id(
+        pool: &SqlitePool,
+        id: String,
+    ) -> Result<Option<CurrencyRate>, AppError> {
         sqlx::query_as::<_, CurrencyRate>("SELECT * FROM currency_rates WHERE id = ?")
             .bind(id)
             .fetch_optional(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
+    }
+
+    pub async fn get_by_pair<'e, E>(
+        executor: E,
+        from_currency: &str,
+        to_currency: &str,
+        year: i32,
+        month: u32,
+    ) -> Result<Option<CurrencyRate>, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, CurrencyRate>(
+            "SELECT * FROM currency_rates WHERE from_currency = ? AND to_currency = ? AND year = ? AND month = ?",
+        )
+        .bind(from_currency)
+        .bind(to_currency)
+        .bind(year)
+        .bind(month)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
     }
 
     // UPSERT
-    // Uniqueness is usually (from_currency, to_currency, month, year) but not enforced by unique constraint in previous migration.
-    // However, for rate logic, we should probably check if a rate for that currency pair exists for that month.
-    // Assuming simple CRUD for now as requested.
+    // With an id the row is edited in place. Without one, (from, to, year, month) is the key:
+    // see `upsert_pair_in_tx`.
     pub async fn upsert(
         pool: &SqlitePool,
         id: Option<String>,
@@ -54,13 +83,17 @@ impl CurrencyRateService {
         rate: f64,
         month: u32,
         year: i32,
-    ) -> Result<CurrencyRate, String> {
-        let now = chrono::Utc::now();
+    ) -> Result<CurrencyRate, AppError> {
+        let exists = match &id {
+            Some(uid) => Self::get_by_id(pool, uid.clone()).await?,
+            None => None,
+        };
+
+        let mut tx = pool.begin().await?;
 
         if let Some(uid) = id {
-            let exists: Option<CurrencyRate> = Self::get_by_id(pool, uid.clone()).await?;
-            if exists.is_some() {
-                return sqlx::query_as::<_, CurrencyRate>(
+            if let Some(existing) = exists {
+                let updated = sqlx::query_as::<_, CurrencyRate>(
                     "UPDATE currency_rates SET from_currency = ?, to_currency = ?, provider = ?, rate = ?, month = ?, year = ?, timestamp = ? WHERE id = ? RETURNING *"
                 )
                 .bind(from_currency)
@@ -69,38 +102,104 @@ impl CurrencyRateService {
                 .bind(rate)
                 .bind(month)
                 .bind(year)
-                .bind(now)
+                .bind(chrono::Utc::now())
                 .bind(uid)
-                .fetch_one(pool)
-                .await
-                .map_err(|e| e.to_string());
+                .fetch_one(&mut *tx)
+                .await?;
+                AuditService::record(
+                    &mut tx,
+                    AUDIT_ENTITY_CURRENCY_RATE,
+                    &updated.id,
+                    Some(&existing),
+                    Some(&updated),
+                )
+                .await?;
+                tx.commit().await?;
+                return Ok(updated);
             }
         }
 
-        let new_id = Uuid::new_v4().to_string();
-        sqlx::query_as::<_, CurrencyRate>(
-            "INSERT INTO currency_rates (id, from_currency, to_currency, provider, rate, month, year, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?, ?) RETURNING *"
+        let rate = Self::upsert_pair_in_tx(
+            &mut tx,
+            &from_currency,
+            &to_currency,
+            &provider,
+            rate,
+            month,
+            year,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(rate)
+    }
+
+    /**
+     * Inserts the rate for a pair and month, or replaces the existing one.
+     * A manual rate is only ever replaced by another manual rate; provider rates leave it untouched
+     * and the stored row is returned as-is.
+     */
+    pub async fn upsert_pair_in_tx(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        from_currency: &str,
+        to_currency: &str,
+        provider: &str,
+        rate: f64,
+        month: u32,
+        year: i32,
+    ) -> Result<CurrencyRate, AppError> {
+        let existing =
+            Self::get_by_pair(&mut **tx, from_currency, to_currency, year, month).await?;
+
+        let stored = sqlx::query_as::<_, CurrencyRate>(
+            "INSERT INTO currency_rates (id, from_currency, to_currency, provider, rate, month, year, timestamp)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (from_currency, to_currency, year, month) DO UPDATE SET
+                 provider = CASE WHEN currency_rates.provider <> $9 OR excluded.provider = $9 THEN excluded.provider ELSE currency_rates.provider END,
+                 rate = CASE WHEN currency_rates.provider <> $9 OR excluded.provider = $9 THEN excluded.rate ELSE currency_rates.rate END,
+                 timestamp = CASE WHEN currency_rates.provider <> $9 OR excluded.provider = $9 THEN excluded.timestamp ELSE currency_rates.timestamp END
+             RETURNING *",
         )
-        .bind(new_id)
+        .bind(Uuid::new_v4().to_string())
         .bind(from_currency)
         .bind(to_currency)
         .bind(provider)
         .bind(rate)
         .bind(month)
         .bind(year)
-        .bind(now)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| e.to_string())
+        .bind(chrono::Utc::now())
+        .bind(MANUAL_PROVIDER)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        AuditService::record(
+            tx,
+            AUDIT_ENTITY_CURRENCY_RATE,
+            &stored.id,
+            existing.as_ref(),
+            Some(&stored),
+        )
+        .await?;
+        Ok(stored)
     }
 
     // DELETE
-    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), String> {
-        sqlx::query("DELETE FROM currency_rates WHERE id = ?")
-            .bind(id)
-            .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let existing = sqlx::query_as::<_, CurrencyRate>(
+            "DELETE FROM currency_rates WHERE id = ? RETURNING *",
+        )
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_CURRENCY_RATE,
+            &id,
+            existing.as_ref(),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
         Ok(())
     }
 }
@@ -170,4 +269,99 @@ mod tests {
             .expect("Failed check");
         assert!(check.is_none());
     }
+
+    #[tokio::test]
+    async fn test_upsert_pair_keeps_one_row_and_manual_precedence() {
+        let pool = setup_test_db().await;
+
+        let synced = CurrencyRateService::upsert(
+            &pool,
+            None,
+            "USD".into(),
+            "NZD".into(),
+            "frankfurter".into(),
+            1.5,
+            1,
+            2025,
+        )
+        .await
+        .expect("synced");
+        let manual = CurrencyRateService::upsert(
+            &pool,
+            None,
+            "USD".into(),
+            "NZD".into(),
+            MANUAL_PROVIDER.into(),
+            1.55,
+            1,
+            2025,
+        )
+        .await
+        .expect("manual");
+        assert_eq!(manual.id, synced.id);
+        assert_eq!(manual.provider, MANUAL_PROVIDER);
+
+        // A provider rate never replaces a manual one
+        let resynced = CurrencyRateService::upsert(
+            &pool,
+            None,
+            "USD".into(),
+            "NZD".into(),
+            "frankfurter".into(),
+            1.6,
+            1,
+            2025,
+        )
+        .await
+        .expect("resynced");
+        assert_eq!(resynced.provider, MANUAL_PROVIDER);
+        assert_eq!(resynced.rate, 1.55);
+
+        let rates = CurrencyRateService::get_all(&pool).await.expect("rates");
+        assert_eq!(rates.len(), 1);
+
+        // The unique index is the authority, even for direct inserts
+        let direct_insert = sqlx::query(
+            "INSERT INTO currency_rates (id, from_currency, to_currency, provider, rate, month, year) VALUES ('dup', 'USD', 'NZD', 'manual', 1.7, 1, 2025)",
+        )
+        .execute(&pool)
+        .await;
+        assert!(direct_insert.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unique_pair_migration_dedupes_with_precedence() {
+        let pool = setup_test_db().await;
+        sqlx::query("DROP INDEX idx_currency_rates_pair_period")
+            .execute(&pool)
+            .await
+            .expect("drop index");
+
+        sqlx::query(
+            "INSERT INTO currency_rates (id, from_currency, to_currency, provider, rate, month, year, timestamp) VALUES
+                ('old-manual', 'USD', 'NZD', 'manual', 1.5, 1, 2025, '2025-01-01 00:00:00'),
+                ('new-synced', 'USD', 'NZD', 'frankfurter', 1.6, 1, 2025, '2025-02-01 00:00:00'),
+                ('old-synced', 'EUR', 'NZD', 'frankfurter', 1.7, 1, 2025, '2025-01-01 00:00:00'),
+                ('new-synced-eur', 'EUR', 'NZD', 'frankfurter', 1.8, 1, 2025, '2025-02-01 00:00:00')",
+        )
+        .execute(&pool)
+        .await
+        .expect("seed duplicates");
+
+        sqlx::raw_sql(include_str!(
+            "../../../migrations/20260204000000_add_currency_rates_unique_pair.sql"
+        ))
+        .execute(&pool)
+        .await
+        .expect("run migration");
+
+        let mut ids: Vec<String> = CurrencyRateService::get_all(&pool)
+            .await
+            .expect("rates")
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["new-synced-eur", "old-manual"]);
+    }
 }
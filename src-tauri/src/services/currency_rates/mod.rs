@@ -1,3 +1,6 @@
+pub mod conversion;
 pub mod currency_rate;
+pub mod rate_import;
 pub mod sync;
+pub mod sync_currency_api;
 pub mod sync_frankfurter;
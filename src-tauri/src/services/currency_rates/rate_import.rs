@@ -0,0 +1,172 @@
+use crate::error::AppError;
+use crate::services::csv_import::{CsvRow, ImportReport, ImportRowStatus};
+use crate::services::currency_rates::currency_rate::{CurrencyRateService, MANUAL_PROVIDER};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+pub type RateImportReport = ImportReport<RateCsvRow>;
+
+/**
+ * A single CSV row. Headers are matched case-insensitively:
+ * from,to,year,month,rate
+ * `rate` is how many `to` units one `from` unit buys, e.g. VND,NZD,2024,1,0.000067
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateCsvRow {
+    #[serde(rename(serialize = "fromCurrency"), alias = "from_currency")]
+    pub from: String,
+    #[serde(rename(serialize = "toCurrency"), alias = "to_currency")]
+    pub to: String,
+    pub year: i32,
+    pub month: u32,
+    pub rate: f64,
+}
+
+impl CsvRow for RateCsvRow {
+    fn validate(&mut self) -> Result<(), AppError> {
+        self.from = self.from.to_uppercase();
+        self.to = self.to.to_uppercase();
+        for (field, currency) in [("from", &self.from), ("to", &self.to)] {
+            if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+                return Err(AppError::validation(
+                    field,
+                    format!("Invalid currency code: '{currency}'"),
+                ));
+            }
+        }
+        if self.from == self.to {
+            return Err(AppError::validation(
+                "to",
+                format!("Rate from {} to itself is always 1", self.from),
+            ));
+        }
+        if !(1..=12).contains(&self.month) {
+            return Err(AppError::validation(
+                "month",
+                format!("Invalid month: {}", self.month),
+            ));
+        }
+        if !self.rate.is_finite() || self.rate <= 0.0 {
+            return Err(AppError::validation(
+                "rate",
+                format!("Invalid rate: {}", self.rate),
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct RateImportService;
+
+impl RateImportService {
+    /**
+     * Stores every valid row as a manual rate in a single transaction.
+     * Existing rates for the same pair and month are replaced and become manual.
+     */
+    pub async fn import_csv(
+        pool: &SqlitePool,
+        contents: &str,
+        dry_run: bool,
+    ) -> Result<RateImportReport, AppError> {
+        let mut report = RateImportReport::new(dry_run);
+        let rows = report.read_rows(contents)?;
+
+        let mut tx = pool.begin().await?;
+
+        for (line, row) in rows {
+            let existing =
+                CurrencyRateService::get_by_pair(&mut *tx, &row.from, &row.to, row.year, row.month)
+                    .await?;
+            let status = match existing {
+                Some(existing)
+                    if existing.provider == MANUAL_PROVIDER && existing.rate == row.rate =>
+                {
+                    ImportRowStatus::Skipped
+                }
+                Some(_) => ImportRowStatus::Updated,
+                None => ImportRowStatus::Created,
+            };
+
+            if status != ImportRowStatus::Skipped {
+                CurrencyRateService::upsert_pair_in_tx(
+                    &mut tx,
+                    &row.from,
+                    &row.to,
+                    MANUAL_PROVIDER,
+                    row.rate,
+                    row.month,
+                    row.year,
+                )
+                .await?;
+            }
+
+            report.push(line, Some(&row), status, None);
+        }
+
+        report.finish(tx).await?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn test_import_rates_creates_updates_and_reports_errors() {
+        let pool = setup_test_db().await;
+
+        // A synced rate for the same pair and month gets replaced by the manual one
+        CurrencyRateService::upsert(
+            &pool,
+            None,
+            "ARS".into(),
+            "NZD".into(),
+            "frankfurter".into(),
+            0.002,
+            2,
+            2024,
+        )
+        .await
+        .expect("synced rate");
+
+        let csv = "From,To,Year,Month,Rate\n\
+                   vnd,NZD,2024,1,0.000067\n\
+                   ARS,NZD,2024,2,0.0019\n\
+                   COP,NZD,2024,13,0.0004\n\
+                   CLP,CLP,2024,1,1\n\
+                   US,NZD,2024,1,1.6\n\
+                   CLP,NZD,2024,1,-1\n";
+
+        let report = RateImportService::import_csv(&pool, csv, false)
+            .await
+            .expect("import");
+
+        assert_eq!(report.created, 1);
+        assert_eq!(report.updated, 1);
+        assert_eq!(report.errored, 4);
+        assert_eq!(report.rows[2].message.as_deref(), Some("Invalid month: 13"));
+
+        let vnd = CurrencyRateService::get_by_pair(&pool, "VND", "NZD", 2024, 1)
+            .await
+            .expect("vnd")
+            .expect("vnd rate stored");
+        assert_eq!(vnd.provider, MANUAL_PROVIDER);
+        let ars = CurrencyRateService::get_by_pair(&pool, "ARS", "NZD", 2024, 2)
+            .await
+            .expect("ars")
+            .expect("ars rate stored");
+        assert_eq!(ars.provider, MANUAL_PROVIDER);
+        assert_eq!(ars.rate, 0.0019);
+
+        let rerun = RateImportService::import_csv(
+            &pool,
+            "from,to,year,month,rate\nARS,NZD,2024,2,0.0019\n",
+            true,
+        )
+        .await
+        .expect("dry run");
+        assert_eq!(rerun.skipped, 1);
+    }
+}
@@ -1,9 +1,13 @@
+use crate::error::AppError;
+use crate::services::audit::with_origin;
 use crate::services::balance_sheet::BalanceSheetService;
-use crate::services::currency_rates::currency_rate::CurrencyRateService;
+use crate::services::currency_rates::currency_rate::{CurrencyRateService, MANUAL_PROVIDER};
+use crate::services::currency_rates::sync_currency_api::CurrencyApiProvider;
 use crate::services::currency_rates::sync_frankfurter::FrankfurterProvider;
 use crate::services::user_settings::UserSettingsService;
 use crate::{models::CurrencyRate, services::account::AccountService};
-use chrono::NaiveDate;
+use chrono::{Datelike, Months, NaiveDate};
+use reqwest::{Client, StatusCode};
 use serde::Deserialize;
 use sqlx::SqlitePool;
 use std::collections::{HashMap, HashSet};
@@ -13,16 +17,48 @@ pub const DECEMBER: u32 = 12;
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum ProviderKind {
     Frankfurter,
+    CurrencyApi,
 }
 
 impl ProviderKind {
     fn as_str(self) -> &'static str {
         match self {
             ProviderKind::Frankfurter => "frankfurter",
+            ProviderKind::CurrencyApi => "currency_api",
         }
     }
 }
 
+/**
+ * An exchange-rate source. Providers only describe their requests and parse responses;
+ * `SyncService` owns the HTTP client, fallback order and ingestion.
+ * Rates are quoted as units of the foreign currency per one unit of the home currency.
+ */
+pub(crate) trait RateProvider: Send + Sync {
+    fn kind(&self) -> ProviderKind;
+
+    fn supports(&self, currency: &str) -> bool;
+
+    // URLs covering `symbols` against `home_currency` between `start` and `end` inclusive.
+    // `settled` months already have a final rate for every symbol and can be left out.
+    fn request_urls(
+        &self,
+        home_currency: &str,
+        symbols: &[String],
+        start: NaiveDate,
+        end: NaiveDate,
+        settled: &HashSet<(i32, u32)>,
+    ) -> Vec<String>;
+
+    // One response body -> at most one rate per (year, month, currency)
+    fn parse(
+        &self,
+        body: &str,
+        home_currency: &str,
+        symbols: &[String],
+    ) -> Result<HashSet<ProviderRate>, AppError>;
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ProviderApiResponse {
     pub rates: HashMap<String, HashMap<String, f64>>,
@@ -69,26 +105,125 @@ pub(crate) struct ProviderRates {
 pub struct SyncService;
 
 impl SyncService {
-    pub async fn sync_exchange_rates(pool: &SqlitePool) -> Result<bool, String> {
+    pub async fn sync_exchange_rates(pool: &SqlitePool) -> Result<bool, AppError> {
+        // Background syncs aren't started from a command, so they're logged under their own origin
+        with_origin(
+            "sync_exchange_rates",
+            Self::sync_exchange_rates_with_providers(pool, &Self::default_providers()),
+        )
+        .await
+    }
+
+    /**
+     * Providers are tried in order. Each one only fetches the currencies that no earlier
+     * provider returned rates for, so later providers act as fallbacks.
+     */
+    pub(crate) async fn sync_exchange_rates_with_providers(
+        pool: &SqlitePool,
+        providers: &[Box<dyn RateProvider>],
+    ) -> Result<bool, AppError> {
         println!("[Sync] Starting exchange rate sync...");
 
         // A) Gather inputs
         let inputs = Self::gather_sync_inputs(pool).await?;
+        if inputs.foreign_currencies.is_empty() {
+            println!("[Sync] No foreign currencies found. Sync skipped.");
+            return Ok(false);
+        }
+
+        let today = chrono::Utc::now().naive_utc().date();
+        let Some(earliest_year) = inputs.years.iter().min().copied() else {
+            println!("[Sync] No balance sheets found. Sync skipped.");
+            return Ok(false);
+        };
+        if earliest_year > today.year() {
+            println!(
+                "[Sync] Earliest balance sheet year {earliest_year} is in the future. Sync skipped."
+            );
+            return Ok(false);
+        }
+        let start = NaiveDate::from_ymd_opt(earliest_year, 1, 1)
+            .ok_or_else(|| AppError::invalid(format!("Invalid year: {earliest_year}")))?;
 
-        // B/C/D) Provider-specific fetching and parsing
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .map_err(|e| AppError::Network(format!("Failed to build HTTP client: {e}")))?;
+
+        // B/C/D) Fetch from each provider the currencies still missing
+        let mut remaining = inputs.foreign_currencies.clone();
+        remaining.sort();
         let mut provider_rates = Vec::new();
-        for provider in Self::provider_candidates() {
-            match provider {
-                ProviderKind::Frankfurter => {
-                    if let Some(rates) = FrankfurterProvider::fetch_provider_rates(&inputs).await? {
-                        provider_rates.push(rates);
-                    }
+        let mut last_error = None;
+        // Why the last provider was skipped, reported when no provider could be used at all
+        let mut unsupported = None;
+
+        for provider in providers {
+            if remaining.is_empty() {
+                break;
+            }
+
+            let name = provider.kind().as_str();
+            if !provider.supports(&inputs.home_currency) {
+                println!(
+                    "[Sync::{name}] Home currency {} is not supported. Skipping provider.",
+                    inputs.home_currency
+                );
+                unsupported = Some(AppError::ProviderUnsupported {
+                    provider: name.to_string(),
+                    currency: inputs.home_currency.clone(),
+                });
+                continue;
+            }
+
+            let symbols: Vec<String> = remaining
+                .iter()
+                .filter(|currency| provider.supports(currency))
+                .cloned()
+                .collect();
+            if symbols.is_empty() {
+                unsupported = Some(AppError::ProviderUnsupported {
+                    provider: name.to_string(),
+                    currency: remaining[0].clone(),
+                });
+                continue;
+            }
+
+            match Self::fetch_provider_rates(
+                &client,
+                provider.as_ref(),
+                &inputs,
+                &symbols,
+                start,
+                today,
+            )
+            .await
+            {
+                Ok(parsed) => {
+                    let covered: HashSet<&String> =
+                        parsed.rates.iter().map(|r| &r.currency).collect();
+                    remaining.retain(|currency| !covered.contains(currency));
+                    provider_rates.push(parsed);
+                }
+                Err(e) => {
+                    eprintln!("[Sync::{name}] {e}. Falling back to the next provider.");
+                    last_error = Some(e);
                 }
             }
         }
 
+        if !remaining.is_empty() {
+            println!(
+                "[Sync] No provider returned rates for: {}",
+                remaining.join(", ")
+            );
+        }
+
         if provider_rates.is_empty() {
-            return Ok(false);
+            return match last_error.or(unsupported) {
+                Some(e) => Err(e),
+                None => Ok(false),
+            };
         }
 
         // E) Ingest per provider using generic interface
@@ -101,10 +236,53 @@ impl SyncService {
         Ok(true)
     }
 
-    async fn gather_sync_inputs(pool: &SqlitePool) -> Result<SyncInputs, String> {
+    async fn fetch_provider_rates(
+        client: &Client,
+        provider: &dyn RateProvider,
+        inputs: &SyncInputs,
+        symbols: &[String],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<ProviderRates, AppError> {
+        let name = provider.kind().as_str();
+        let home_currency = &inputs.home_currency;
+        println!(
+            "[Sync::{name}] Fetching rates for {} from {start}..{end} with base currency: {home_currency}",
+            symbols.join(","),
+        );
+
+        let mut rates = HashSet::new();
+        let settled = Self::settled_months(inputs, symbols, start, end);
+        for request_url in provider.request_urls(home_currency, symbols, start, end, &settled) {
+            let resp = client.get(&request_url).send().await?;
+
+            // Snapshots can be missing for individual days; that only leaves a gap
+            if resp.status() == StatusCode::NOT_FOUND {
+                println!("[Sync::{name}] No data at {request_url}");
+                continue;
+            }
+            if !resp.status().is_success() {
+                return Err(AppError::Network(format!("API error: {}", resp.status())));
+            }
+
+            let body = resp.text().await?;
+            for rate in provider.parse(&body, home_currency, symbols)? {
+                rates.insert(rate);
+            }
+        }
+
+        Ok(ProviderRates {
+            provider: provider.kind(),
+            rates,
+        })
+    }
+
+    async fn gather_sync_inputs(pool: &SqlitePool) -> Result<SyncInputs, AppError> {
         // Home currency
         let settings_list = UserSettingsService::get_all(pool).await?;
-        let settings = settings_list.first().ok_or("User settings not found")?;
+        let settings = settings_list
+            .first()
+            .ok_or_else(|| AppError::missing("User settings"))?;
         let home_currency = settings.home_currency.clone();
 
         // Foreign currencies from accounts
@@ -143,15 +321,86 @@ impl SyncService {
         })
     }
 
-    fn provider_candidates() -> Vec<ProviderKind> {
-        vec![ProviderKind::Frankfurter]
+    // Months from `start` to `end` in which every symbol already has a final rate
+    fn settled_months(
+        inputs: &SyncInputs,
+        symbols: &[String],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> HashSet<(i32, u32)> {
+        let mut settled = HashSet::new();
+        let mut month_start = NaiveDate::from_ymd_opt(start.year(), start.month(), 1);
+        while let Some(first_day) = month_start.filter(|day| *day <= end) {
+            let (year, month) = (first_day.year(), first_day.month());
+            if symbols.iter().all(|currency| {
+                let key = format!("{year}-{month}-{currency}-{}", inputs.home_currency);
+                Self::is_settled(inputs.existing_rates.get(&key), year, month)
+            }) {
+                settled.insert((year, month));
+            }
+            month_start = first_day.checked_add_months(Months::new(1));
+        }
+        settled
+    }
+
+    // Manual rates and rates fetched after their month ended won't change on a later sync
+    fn is_settled(rate: Option<&CurrencyRate>, year: i32, month: u32) -> bool {
+        rate.is_some_and(|rate| {
+            rate.provider == MANUAL_PROVIDER
+                || rate.timestamp.naive_utc().date() > Self::last_day_of_month(year, month)
+        })
+    }
+
+    fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
+        if month == DECEMBER {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)
+                .unwrap()
+                .pred_opt()
+                .unwrap()
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)
+                .unwrap()
+                .pred_opt()
+                .unwrap()
+        }
+    }
+
+    async fn upsert_provider_rate(
+        pool: &SqlitePool,
+        from_currency: &str,
+        to_currency: &str,
+        provider: ProviderKind,
+        rate: f64,
+        month: u32,
+        year: i32,
+    ) -> Result<CurrencyRate, AppError> {
+        let mut tx = pool.begin().await?;
+        let stored = CurrencyRateService::upsert_pair_in_tx(
+            &mut tx,
+            from_currency,
+            to_currency,
+            provider.as_str(),
+            rate,
+            month,
+            year,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(stored)
+    }
+
+    fn default_providers() -> Vec<Box<dyn RateProvider>> {
+        vec![
+            Box::new(FrankfurterProvider::default()),
+            Box::new(CurrencyApiProvider::default()),
+        ]
     }
 
     async fn ingest_provider_rates(
         pool: &SqlitePool,
         parsed: &ProviderRates,
         inputs: &SyncInputs,
-    ) -> Result<(), String> {
+    ) -> Result<(), AppError> {
         let provider = parsed.provider;
         let rates_list = &parsed.rates;
         let home_currency = &inputs.home_currency;
@@ -168,28 +417,11 @@ impl SyncService {
         for rate in rates_list {
             let year = rate.year;
             let month = rate.month;
-
-            let last_day = if month == DECEMBER {
-                NaiveDate::from_ymd_opt(year + 1, 1, 1)
-                    .unwrap()
-                    .pred_opt()
-                    .unwrap()
-            } else {
-                NaiveDate::from_ymd_opt(year, month + 1, 1)
-                    .unwrap()
-                    .pred_opt()
-                    .unwrap()
-            };
-
             let foreign = &rate.currency;
             let rate_in_home = rate.value;
 
             let key = format!("{year}-{month}-{foreign}-{home_currency}");
-            let existing_rate_for_end_of_month = match existing_rates.get(&key) {
-                Some(rate) => rate.timestamp.naive_utc().date() > last_day,
-                None => false,
-            };
-            if existing_rate_for_end_of_month {
+            if Self::is_settled(existing_rates.get(&key), year, month) {
                 continue;
             }
 
@@ -203,13 +435,12 @@ impl SyncService {
                 "[Sync] Upserting rate for {year}-{month:02} ({foreign}->{home_currency}): {inverted_rate}",
             );
 
-            let existing_id = existing_rates.get(&key).map(|r| r.id.clone());
-            match CurrencyRateService::upsert(
+            // The snapshot above may be stale, so the write itself keeps manual rates in place
+            match Self::upsert_provider_rate(
                 pool,
-                existing_id,
-                foreign.clone(),
-                home_currency.to_string(),
-                provider.as_str().to_string(),
+                foreign,
+                home_currency,
+                provider,
                 inverted_rate,
                 month,
                 year,
@@ -235,7 +466,7 @@ mod tests {
     use crate::services::currency_rates::currency_rate::CurrencyRateService;
 
     use super::*;
-    use wiremock::matchers::{method, query_param};
+    use wiremock::matchers::{method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
@@ -312,7 +543,9 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = SyncService::sync_exchange_rates(&pool).await;
+        let providers: Vec<Box<dyn RateProvider>> =
+            vec![Box::new(FrankfurterProvider::new(&mock_server.uri()))];
+        let result = SyncService::sync_exchange_rates_with_providers(&pool, &providers).await;
 
         assert!(
             matches!(result, Ok(true)),
@@ -336,4 +569,218 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_sync_falls_back_to_next_provider_for_unsupported_currencies() {
+        let pool = crate::test_utils::setup_test_db().await;
+
+        UserSettingsService::upsert(&pool, "Test User".into(), "NZD".into(), "Light".into())
+            .await
+            .expect("Failed to create settings");
+        for (name, currency) in [("US Bank", "USD"), ("VN Bank", "VND")] {
+            AccountService::upsert(
+                &pool,
+                None,
+                name.into(),
+                "Asset".into(),
+                currency.into(),
+                None,
+            )
+            .await
+            .expect("Failed to create account");
+        }
+        let today = chrono::Utc::now().naive_utc().date();
+        BalanceSheetService::upsert(&pool, None, today.year())
+            .await
+            .expect("Failed to create balance sheet");
+
+        // Frankfurter doesn't publish VND, so only USD is requested from it
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("symbols", "USD"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                format!(r#"{{"rates": {{"{today}": {{"USD": 0.6}}}}}}"#),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        // Earlier month-end snapshots fall through to wiremock's 404 and are skipped
+        Mock::given(method("GET"))
+            .and(path("/currency-api@latest/v1/currencies/nzd.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                format!(r#"{{"date": "{today}", "nzd": {{"usd": 0.61, "vnd": 15000.0}}}}"#),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let providers: Vec<Box<dyn RateProvider>> = vec![
+            Box::new(FrankfurterProvider::new(&mock_server.uri())),
+            Box::new(CurrencyApiProvider::new(&format!(
+                "{}/currency-api",
+                mock_server.uri()
+            ))),
+        ];
+        let result = SyncService::sync_exchange_rates_with_providers(&pool, &providers).await;
+        assert!(
+            matches!(result, Ok(true)),
+            "Sync should succeed: {result:?}"
+        );
+
+        let all_rates = CurrencyRateService::get_all(&pool)
+            .await
+            .expect("Failed to get rates");
+        let provider_for = |currency: &str| {
+            all_rates
+                .iter()
+                .find(|r| r.from_currency == currency && r.to_currency == "NZD")
+                .map(|r| r.provider.as_str())
+        };
+        assert_eq!(provider_for("USD"), Some("frankfurter"));
+        assert_eq!(provider_for("VND"), Some("currency_api"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_reports_unsupported_home_currency() {
+        let pool = crate::test_utils::setup_test_db().await;
+
+        UserSettingsService::upsert(&pool, "Test User".into(), "VND".into(), "Light".into())
+            .await
+            .expect("Failed to create settings");
+        AccountService::upsert(
+            &pool,
+            None,
+            "US Bank".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("Failed to create account");
+        let today = chrono::Utc::now().naive_utc().date();
+        BalanceSheetService::upsert(&pool, None, today.year())
+            .await
+            .expect("Failed to create balance sheet");
+
+        // Frankfurter doesn't publish VND, so nothing is requested at all
+        let mock_server = MockServer::start().await;
+        let providers: Vec<Box<dyn RateProvider>> =
+            vec![Box::new(FrankfurterProvider::new(&mock_server.uri()))];
+        let result = SyncService::sync_exchange_rates_with_providers(&pool, &providers).await;
+        assert_eq!(
+            result,
+            Err(AppError::ProviderUnsupported {
+                provider: "frankfurter".into(),
+                currency: "VND".into(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_never_overwrites_manual_rates() {
+        let pool = crate::test_utils::setup_test_db().await;
+
+        UserSettingsService::upsert(&pool, "Test User".into(), "NZD".into(), "Light".into())
+            .await
+            .expect("Failed to create settings");
+        AccountService::upsert(
+            &pool,
+            None,
+            "US Bank".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("Failed to create account");
+        BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("Failed to create balance sheet");
+        CurrencyRateService::upsert(
+            &pool,
+            None,
+            "USD".into(),
+            "NZD".into(),
+            MANUAL_PROVIDER.into(),
+            1.5,
+            1,
+            2024,
+        )
+        .await
+        .expect("Failed to create manual rate");
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"rates": {"2024-01-31": {"USD": 0.6}, "2024-02-29": {"USD": 0.625}}}"#,
+                "application/json",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let providers: Vec<Box<dyn RateProvider>> =
+            vec![Box::new(FrankfurterProvider::new(&mock_server.uri()))];
+        SyncService::sync_exchange_rates_with_providers(&pool, &providers)
+            .await
+            .expect("Sync should succeed");
+
+        let january = CurrencyRateService::get_by_pair(&pool, "USD", "NZD", 2024, 1)
+            .await
+            .expect("january")
+            .expect("january rate");
+        assert_eq!(january.provider, MANUAL_PROVIDER);
+        assert_eq!(january.rate, 1.5);
+
+        let february = CurrencyRateService::get_by_pair(&pool, "USD", "NZD", 2024, 2)
+            .await
+            .expect("february")
+            .expect("february rate");
+        assert_eq!(february.provider, "frankfurter");
+        assert_eq!(february.rate, 1.6);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_keeps_manual_rates_entered_during_a_sync() {
+        let pool = crate::test_utils::setup_test_db().await;
+
+        // Inputs gathered before the manual rate was entered
+        let inputs = SyncInputs {
+            home_currency: "NZD".into(),
+            foreign_currencies: vec!["USD".into()],
+            years: vec![2024],
+            existing_rates: HashMap::new(),
+        };
+        CurrencyRateService::upsert(
+            &pool,
+            None,
+            "USD".into(),
+            "NZD".into(),
+            MANUAL_PROVIDER.into(),
+            1.5,
+            1,
+            2024,
+        )
+        .await
+        .expect("Failed to create manual rate");
+
+        let parsed = ProviderRates {
+            provider: ProviderKind::Frankfurter,
+            rates: HashSet::from([ProviderRate {
+                year: 2024,
+                month: 1,
+                currency: "USD".into(),
+                value: 0.6,
+            }]),
+        };
+        SyncService::ingest_provider_rates(&pool, &parsed, &inputs)
+            .await
+            .expect("ingest");
+
+        let rates = CurrencyRateService::get_all(&pool).await.expect("rates");
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].provider, MANUAL_PROVIDER);
+        assert_eq!(rates[0].rate, 1.5);
+    }
 }
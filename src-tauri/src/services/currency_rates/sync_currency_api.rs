@@ -0,0 +1,219 @@
+use crate::error::AppError;
+use chrono::{Datelike, Months, NaiveDate};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::services::currency_rates::sync::{ProviderKind, ProviderRate, RateProvider};
+
+const CURRENCY_API_BASE_URL: &str = "https://cdn.jsdelivr.net/npm/@fawazahmed0/currency-api";
+const CURRENCY_API_DATE_FORMAT: &str = "%Y-%m-%d";
+const CURRENCY_API_LATEST: &str = "latest";
+// The oldest dated snapshot the API publishes; earlier dates return 404
+const CURRENCY_API_FIRST_SNAPSHOT: NaiveDate = match NaiveDate::from_ymd_opt(2024, 3, 2) {
+    Some(date) => date,
+    None => panic!("invalid first snapshot date"),
+};
+// ISO 4217 codes, precious metals and the major cryptocurrencies the API publishes
+const SUPPORTED_CURRENCIES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+    "CDF", "CHF", "CLP", "CNY", "COP", "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD",
+    "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ",
+    "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD",
+    "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR",
+    "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN",
+    "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR",
+    "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB",
+    "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS",
+    "VES", "VND", "VUV", "WST", "XAF", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWL", "XAU",
+    "XAG", "XPT", "XPD", "BTC", "ETH",
+];
+
+/**
+ * Daily snapshot for one base currency, keyed by the lowercase base code.
+ * Given {"date": "2024-03-31", "nzd": {"usd": 0.5974, "vnd": 14893.1}}
+ */
+#[derive(Deserialize, Debug)]
+struct CurrencyApiResponse {
+    date: String,
+    #[serde(flatten)]
+    rates: HashMap<String, serde_json::Value>,
+}
+
+/**
+ * Community currency API (https://github.com/fawazahmed0/exchange-api), used as a fallback
+ * for the currencies Frankfurter doesn't publish. It serves one snapshot per day, so one
+ * request is made per month-end in the range that isn't settled yet.
+ */
+pub struct CurrencyApiProvider {
+    base_url: String,
+}
+
+impl Default for CurrencyApiProvider {
+    fn default() -> Self {
+        Self::new(CURRENCY_API_BASE_URL)
+    }
+}
+
+impl RateProvider for CurrencyApiProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::CurrencyApi
+    }
+
+    fn supports(&self, currency: &str) -> bool {
+        SUPPORTED_CURRENCIES.contains(&currency)
+    }
+
+    fn request_urls(
+        &self,
+        home_currency: &str,
+        _symbols: &[String],
+        start: NaiveDate,
+        end: NaiveDate,
+        settled: &HashSet<(i32, u32)>,
+    ) -> Vec<String> {
+        let base = home_currency.to_lowercase();
+        let current_month = (end.year(), end.month());
+        let start = start.max(CURRENCY_API_FIRST_SNAPSHOT);
+
+        let mut urls = Vec::new();
+        let mut month_start = NaiveDate::from_ymd_opt(start.year(), start.month(), 1);
+        while let Some(first_day) = month_start {
+            if (first_day.year(), first_day.month()) > current_month {
+                break;
+            }
+            month_start = first_day.checked_add_months(Months::new(1));
+            if settled.contains(&(first_day.year(), first_day.month())) {
+                continue;
+            }
+
+            // The current month isn't over yet, so take the most recent snapshot instead
+            let snapshot = if (first_day.year(), first_day.month()) == current_month {
+                CURRENCY_API_LATEST.to_string()
+            } else {
+                let last_day = first_day + Months::new(1) - chrono::Duration::days(1);
+                last_day.format(CURRENCY_API_DATE_FORMAT).to_string()
+            };
+            urls.push(format!(
+                "{}@{snapshot}/v1/currencies/{base}.json",
+                self.base_url
+            ));
+        }
+        urls
+    }
+
+    fn parse(
+        &self,
+        body: &str,
+        home_currency: &str,
+        symbols: &[String],
+    ) -> Result<HashSet<ProviderRate>, AppError> {
+        let data: CurrencyApiResponse = serde_json::from_str(body)
+            .map_err(|e| AppError::Network(format!("Failed to parse JSON: {e}")))?;
+        let date = NaiveDate::parse_from_str(&data.date, CURRENCY_API_DATE_FORMAT)
+            .map_err(|e| AppError::Network(format!("Invalid snapshot date {}: {e}", data.date)))?;
+
+        let Some(rates) = data
+            .rates
+            .get(&home_currency.to_lowercase())
+            .and_then(|value| value.as_object())
+        else {
+            return Ok(HashSet::new());
+        };
+
+        Ok(symbols
+            .iter()
+            .filter_map(|currency| {
+                let value = rates.get(&currency.to_lowercase())?.as_f64()?;
+                Some(ProviderRate {
+                    year: date.year(),
+                    month: date.month(),
+                    currency: currency.clone(),
+                    value,
+                })
+            })
+            .collect())
+    }
+}
+
+impl CurrencyApiProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_api_request_urls_per_month_end() {
+        let provider = CurrencyApiProvider::new("http://localhost/api/");
+        let urls = provider.request_urls(
+            "NZD",
+            &["VND".to_string()],
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(),
+            &HashSet::new(),
+        );
+
+        assert_eq!(
+            urls,
+            vec![
+                "http://localhost/api@2025-01-31/v1/currencies/nzd.json",
+                "http://localhost/api@2025-02-28/v1/currencies/nzd.json",
+                "http://localhost/api@latest/v1/currencies/nzd.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_currency_api_skips_settled_months_and_unpublished_dates() {
+        let provider = CurrencyApiProvider::new("http://localhost/api");
+        let urls = provider.request_urls(
+            "NZD",
+            &["VND".to_string()],
+            NaiveDate::from_ymd_opt(2015, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 6, 12).unwrap(),
+            &HashSet::from([(2024, 4), (2024, 5)]),
+        );
+
+        // Nothing is published before March 2024, and April and May are already stored
+        assert_eq!(
+            urls,
+            vec![
+                "http://localhost/api@2024-03-31/v1/currencies/nzd.json",
+                "http://localhost/api@latest/v1/currencies/nzd.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_currency_api_supports_known_codes_only() {
+        let provider = CurrencyApiProvider::default();
+        assert!(provider.supports("VND"));
+        assert!(provider.supports("BTC"));
+        assert!(!provider.supports("XYZ"));
+        assert!(!provider.supports("vnd"));
+    }
+
+    #[test]
+    fn test_currency_api_parse_filters_symbols() {
+        let provider = CurrencyApiProvider::default();
+        let body =
+            r#"{"date": "2024-02-29", "nzd": {"usd": 0.6087, "vnd": 14893.1, "eur": 0.5629}}"#;
+
+        let rates = provider
+            .parse(body, "NZD", &["VND".to_string(), "XYZ".to_string()])
+            .expect("parse");
+
+        assert_eq!(rates.len(), 1);
+        let vnd = rates.iter().next().unwrap();
+        assert_eq!((vnd.year, vnd.month), (2024, 2));
+        assert_eq!(vnd.currency, "VND");
+        assert_eq!(vnd.value, 14893.1);
+    }
+}
@@ -1,9 +1,9 @@
+use crate::error::AppError;
 use chrono::{Datelike, NaiveDate};
-use reqwest::Client;
 use std::collections::{HashMap, HashSet};
 
 use crate::services::currency_rates::sync::{
-    ProviderApiResponse, ProviderKind, ProviderRate, ProviderRates, SyncInputs,
+    ProviderApiResponse, ProviderKind, ProviderRate, RateProvider,
 };
 
 const FRANKFURTER_BASE_URL: &str = "https://api.frankfurter.dev/v1";
@@ -15,96 +15,61 @@ const SUPPORTED_CURRENCIES: &[&str] = &[
 ];
 
 /**
- * Finds the most recent date of any given month and returns those currency rates
- * Given {"2024-12-31": {"EUR": 0.5516, "USD": 0.5913}, ...}
- * Return {ProviderRate(2024, 12, "EUR", 0.5516), ...}
+ * ECB reference rates via https://frankfurter.dev, for the ~30 currencies the ECB publishes
  */
-pub struct FrankfurterProvider;
-
-impl FrankfurterProvider {
-    pub async fn fetch_provider_rates(
-        inputs: &SyncInputs,
-    ) -> Result<Option<ProviderRates>, String> {
-        if inputs.foreign_currencies.is_empty() {
-            println!("[Sync::Frankfurter] No foreign currencies found. Sync skipped.");
-            return Ok(None);
-        }
-
-        if inputs.years.is_empty() {
-            println!("[Sync::Frankfurter] No balance sheets found. Sync skipped.");
-            return Ok(None);
-        }
-
-        if !Self::is_supported_currency(&inputs.home_currency) {
-            println!(
-                "[Sync::Frankfurter] Home currency {} is not supported by Frankfurter. Skipping provider.",
-                inputs.home_currency
-            );
-            return Ok(None);
-        }
-
-        let (supported, unsupported) =
-            Self::split_supported_currencies(inputs.foreign_currencies.clone());
-        if !unsupported.is_empty() {
-            println!(
-                "[Sync::Frankfurter] Skipping unsupported Frankfurter currencies: {}",
-                unsupported.join(", ")
-            );
-        }
-
-        if supported.is_empty() {
-            println!(
-                "[Sync::Frankfurter] No Frankfurter-supported foreign currencies found. Skipping provider."
-            );
-            return Ok(None);
-        }
-
-        let today = chrono::Utc::now().naive_utc().date();
-        let today_year = today.year();
-        let earliest_year = *inputs.years.iter().min().unwrap_or(&today_year);
-        if earliest_year > today_year {
-            println!(
-                "[Sync::Frankfurter] Earliest balance sheet year {earliest_year} is in the future. Sync skipped."
-            );
-            return Ok(None);
-        }
-
-        let start_date = format!("{earliest_year}-01-01");
-        let end_date = format!("{}", today.format("%Y-%m-%d"));
-        let symbols = supported.join(",");
-        let home_currency = &inputs.home_currency;
+pub struct FrankfurterProvider {
+    base_url: String,
+}
 
-        println!(
-            "[Sync::Frankfurter] Fetching rates for {symbols} from {start_date}..{end_date} with base currency: {home_currency}",
-        );
+impl Default for FrankfurterProvider {
+    fn default() -> Self {
+        Self::new(FRANKFURTER_BASE_URL)
+    }
+}
 
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| format!("Failed to build HTTP client: {e}"))?;
+impl RateProvider for FrankfurterProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Frankfurter
+    }
 
-        let request_url =
-            format!("{FRANKFURTER_BASE_URL}/{start_date}..{end_date}?base={home_currency}&symbols={symbols}");
+    fn supports(&self, currency: &str) -> bool {
+        Self::is_supported_currency(currency)
+    }
 
-        let resp = match client.get(&request_url).send().await {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("[Sync::Frankfurter] Request error: {e}");
-                return Err(format!("Request error: {e}"));
-            }
-        };
+    // The time series endpoint returns every business day in the range in one response
+    fn request_urls(
+        &self,
+        home_currency: &str,
+        symbols: &[String],
+        start: NaiveDate,
+        end: NaiveDate,
+        _settled: &HashSet<(i32, u32)>,
+    ) -> Vec<String> {
+        let start_date = start.format(FRANKFURTER_DATE_FORMAT);
+        let end_date = end.format(FRANKFURTER_DATE_FORMAT);
+        let symbols = symbols.join(",");
+        vec![format!(
+            "{}/{start_date}..{end_date}?base={home_currency}&symbols={symbols}",
+            self.base_url
+        )]
+    }
 
-        if !resp.status().is_success() {
-            eprintln!("[Sync::Frankfurter] API error: Status {:?}", resp.status());
-            return Err(format!("API error: {}", resp.status()));
-        }
+    fn parse(
+        &self,
+        body: &str,
+        _home_currency: &str,
+        _symbols: &[String],
+    ) -> Result<HashSet<ProviderRate>, AppError> {
+        let data: ProviderApiResponse = serde_json::from_str(body)
+            .map_err(|e| AppError::Network(format!("Failed to parse JSON: {e}")))?;
+        Ok(Self::parse_rates_for_most_recent(data.rates))
+    }
+}
 
-        match resp.json::<ProviderApiResponse>().await {
-            Ok(data) => Ok(Some(ProviderRates {
-                provider: ProviderKind::Frankfurter,
-                rates: Self::parse_rates_for_most_recent(data.rates),
-            })),
-            Err(e) => Err(format!("Failed to parse JSON: {e}")),
+impl FrankfurterProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
         }
     }
 
@@ -112,21 +77,6 @@ impl FrankfurterProvider {
         SUPPORTED_CURRENCIES.contains(&currency)
     }
 
-    fn split_supported_currencies(currencies: Vec<String>) -> (Vec<String>, Vec<String>) {
-        let mut supported = Vec::new();
-        let mut unsupported = Vec::new();
-
-        for currency in currencies {
-            if Self::is_supported_currency(&currency) {
-                supported.push(currency);
-            } else {
-                unsupported.push(currency);
-            }
-        }
-
-        (supported, unsupported)
-    }
-
     /**
      * Finds the most recent date of any given month and returns those currency rates
      * Given {"2024-12-31": {"EUR": 0.5516, "USD": 0.5913}, ...}
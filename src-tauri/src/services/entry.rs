@@ -1,97 +1,602 @@
+use crate::error::AppError;
 use crate::models::Entry;
-use sqlx::SqlitePool;
+use crate::services::account::AccountService;
+use crate::services::audit::AuditService;
+use crate::services::balance_sheet::BalanceSheetService;
+use crate::services::recurring_rule::RecurringRuleService;
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Sqlite, SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+pub const ENTRY_STATUS_CONFIRMED: &str = "confirmed";
+pub const ENTRY_STATUS_ESTIMATED: &str = "estimated";
+pub const ENTRY_STATUS_IMPORTED: &str = "imported";
+pub const ENTRY_STATUS_CARRIED_FORWARD: &str = "carried_forward";
+
+pub const ENTRY_SOURCE_CSV_IMPORT: &str = "csv_import";
+pub const ENTRY_SOURCE_RECURRING_RULE: &str = "recurring_rule";
+pub const ENTRY_SOURCE_CARRY_FORWARD: &str = "carry_forward";
+
+/**
+ * Where an entry's value came from and how much it can be trusted.
+ * Defaults to a confirmed value with no source or note.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryProvenance {
+    pub status: String,
+    pub source: Option<String>,
+    pub note: Option<String>,
+}
+
+impl Default for EntryProvenance {
+    fn default() -> Self {
+        Self::new(ENTRY_STATUS_CONFIRMED, None)
+    }
+}
+
+impl EntryProvenance {
+    pub fn new(status: &str, source: Option<&str>) -> Self {
+        Self {
+            status: status.to_string(),
+            source: source.map(str::to_string),
+            note: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        match self.status.as_str() {
+            ENTRY_STATUS_CONFIRMED
+            | ENTRY_STATUS_ESTIMATED
+            | ENTRY_STATUS_IMPORTED
+            | ENTRY_STATUS_CARRIED_FORWARD => Ok(()),
+            other => Err(AppError::validation(
+                "status",
+                format!("Unknown entry status: {other}"),
+            )),
+        }
+    }
+
+    // Values the user hasn't verified against a statement or import
+    pub fn is_estimated_status(status: &str) -> bool {
+        matches!(
+            status,
+            ENTRY_STATUS_ESTIMATED | ENTRY_STATUS_CARRIED_FORWARD
+        )
+    }
+}
+
+/**
+ * An entry a recurring rule would write. `existing_amount` is the entry already stored
+ * for that account and month, if any; applying skips those unless asked to overwrite.
+ */
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftEntry {
+    pub account_id: String,
+    pub rule_id: String,
+    pub month: u32,
+    pub amount: f64,
+    pub existing_amount: Option<f64>,
+}
+
+// One cell of the balance sheet grid, as saved by `upsert_entries`.
+// Without a provenance a stored cell keeps its own, and a new one is confirmed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryCell {
+    pub account_id: String,
+    pub month: u32,
+    pub amount: f64,
+    #[serde(default)]
+    pub provenance: Option<EntryProvenance>,
+}
+
 pub struct EntryService;
 
 impl EntryService {
-    // LIST All (Required by standard)
-    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Entry>, String> {
+    // LIST All (only tests read every entry at once; the app goes through a sheet)
+    #[cfg(test)]
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Entry>, AppError> {
         sqlx::query_as::<_, Entry>("SELECT * FROM entries")
             .fetch_all(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
     }
 
-    // LIST by Balance Sheet
+    // LIST by Balance Sheet, optionally only entries with the given status
     pub async fn get_by_balance_sheet(
         pool: &SqlitePool,
         balance_sheet_id: String,
-    ) -> Result<Vec<Entry>, String> {
-        sqlx::query_as::<_, Entry>("SELECT * FROM entries WHERE balance_sheet_id = ?")
-            .bind(balance_sheet_id)
-            .fetch_all(pool)
-            .await
-            .map_err(|e| e.to_string())
+        status: Option<String>,
+    ) -> Result<Vec<Entry>, AppError> {
+        sqlx::query_as::<_, Entry>(
+            "SELECT * FROM entries WHERE balance_sheet_id = $1 AND ($2 IS NULL OR status = $2)",
+        )
+        .bind(balance_sheet_id)
+        .bind(status)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
     }
 
     // READ
     #[allow(dead_code)]
-    pub async fn get_by_id(pool: &SqlitePool, id: String) -> Result<Option<Entry>, String> {
+    pub async fn get_by_id(pool: &SqlitePool, id: String) -> Result<Option<Entry>, AppError> {
         sqlx::query_as::<_, Entry>("SELECT * FROM entries WHERE id = ?")
             .bind(id)
             .fetch_optional(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
+    }
+
+    // READ by business keys: balance_sheet + account + month
+    pub async fn get_by_key<'e, E>(
+        executor: E,
+        balance_sheet_id: &str,
+        account_id: &str,
+        month: i32,
+    ) -> Result<Option<Entry>, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, Entry>(
+            "SELECT * FROM entries WHERE balance_sheet_id = ? AND account_id = ? AND month = ?",
+        )
+        .bind(balance_sheet_id)
+        .bind(account_id)
+        .bind(month)
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::from)
     }
 
-    // UPSERT (Create or Update based on business keys: balance_sheet + account + month)
+    // UPSERT (Create or Update based on business keys: balance_sheet + account + month, which are unique)
     pub async fn upsert(
         pool: &SqlitePool,
         balance_sheet_id: String,
         account_id: String,
         month: i32,
         amount: f64,
-    ) -> Result<Entry, String> {
-        let existing: Option<Entry> = sqlx::query_as(
-            "SELECT * FROM entries WHERE balance_sheet_id = ? AND account_id = ? AND month = ?",
+        provenance: EntryProvenance,
+    ) -> Result<Entry, AppError> {
+        let cell = EntryCell {
+            account_id,
+            month: u32::try_from(month)
+                .map_err(|_| AppError::validation("month", format!("Invalid month: {month}")))?,
+            amount,
+            provenance: Some(provenance),
+        };
+        Self::validate_cells(pool, &balance_sheet_id, std::slice::from_ref(&cell)).await?;
+
+        let mut tx = pool.begin().await?;
+
+        let entry = Self::upsert_in_tx(
+            &mut tx,
+            balance_sheet_id,
+            cell.account_id,
+            month,
+            amount,
+            cell.provenance.unwrap_or_default(),
         )
-        .bind(&balance_sheet_id)
-        .bind(&account_id)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(entry)
+    }
+
+    pub async fn upsert_in_tx(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        balance_sheet_id: String,
+        account_id: String,
+        month: i32,
+        amount: f64,
+        provenance: EntryProvenance,
+    ) -> Result<Entry, AppError> {
+        provenance.validate()?;
+        // Only read for the audit log; the write itself is a single atomic statement
+        let existing = Self::get_by_key(&mut **tx, &balance_sheet_id, &account_id, month).await?;
+
+        let entry = Self::upsert_row(
+            tx,
+            &balance_sheet_id,
+            &account_id,
+            month,
+            amount,
+            &provenance,
+        )
+        .await?;
+
+        AuditService::record_entry(tx, existing.as_ref(), Some(&entry)).await?;
+        Ok(entry)
+    }
+
+    /**
+     * Saves many cells of one balance sheet in a single transaction and returns the stored entries.
+     * Every cell is validated before anything is written, so a bad cell saves nothing.
+     */
+    pub async fn upsert_many(
+        pool: &SqlitePool,
+        balance_sheet_id: String,
+        cells: Vec<EntryCell>,
+    ) -> Result<Vec<Entry>, AppError> {
+        Self::validate_cells(pool, &balance_sheet_id, &cells).await?;
+
+        let mut tx = pool.begin().await?;
+        let existing: HashMap<(String, u32), Entry> =
+            sqlx::query_as::<_, Entry>("SELECT * FROM entries WHERE balance_sheet_id = ?")
+                .bind(&balance_sheet_id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .map(|entry| ((entry.account_id.clone(), entry.month), entry))
+                .collect();
+
+        let mut entries = Vec::with_capacity(cells.len());
+        for cell in cells {
+            let previous = existing.get(&(cell.account_id.clone(), cell.month));
+            let provenance = match (cell.provenance, previous) {
+                (Some(provenance), _) => provenance,
+                (None, Some(entry)) => EntryProvenance {
+                    status: entry.status.clone(),
+                    source: entry.source.clone(),
+                    note: entry.note.clone(),
+                },
+                (None, None) => EntryProvenance::default(),
+            };
+            // Untouched cells are neither rewritten nor audited
+            if let Some(entry) = previous.filter(|entry| {
+                entry.amount == cell.amount
+                    && entry.status == provenance.status
+                    && entry.source == provenance.source
+                    && entry.note == provenance.note
+            }) {
+                entries.push(entry.clone());
+                continue;
+            }
+
+            let entry = Self::upsert_row(
+                &mut tx,
+                &balance_sheet_id,
+                &cell.account_id,
+                cell.month as i32,
+                cell.amount,
+                &provenance,
+            )
+            .await?;
+            AuditService::record_entry(&mut tx, previous, Some(&entry)).await?;
+            entries.push(entry);
+        }
+        tx.commit().await?;
+
+        Ok(entries)
+    }
+
+    /**
+     * Rejects cells that can't be stored: a sheet or account that doesn't exist or is in the
+     * trash, a month outside 1-12, an amount that isn't a number, or two values for one month.
+     */
+    async fn validate_cells(
+        pool: &SqlitePool,
+        balance_sheet_id: &str,
+        cells: &[EntryCell],
+    ) -> Result<(), AppError> {
+        if BalanceSheetService::get_by_id(pool, balance_sheet_id.to_string())
+            .await?
+            .is_none()
+        {
+            return Err(AppError::not_found("BalanceSheet", balance_sheet_id));
+        }
+
+        let account_ids: HashSet<String> = AccountService::get_all(pool, true)
+            .await?
+            .into_iter()
+            .map(|account| account.id)
+            .collect();
+        let mut seen = HashSet::new();
+        for cell in cells {
+            if !(1..=12).contains(&cell.month) {
+                return Err(AppError::validation(
+                    "month",
+                    format!("Invalid month: {}", cell.month),
+                ));
+            }
+            if !cell.amount.is_finite() {
+                return Err(AppError::validation(
+                    "amount",
+                    format!("Invalid amount: {}", cell.amount),
+                ));
+            }
+            if !account_ids.contains(&cell.account_id) {
+                return Err(AppError::not_found("Account", cell.account_id.clone()));
+            }
+            if let Some(provenance) = &cell.provenance {
+                provenance.validate()?;
+            }
+            if !seen.insert((cell.account_id.as_str(), cell.month)) {
+                return Err(AppError::validation(
+                    "cells",
+                    format!(
+                        "Account {} has more than one value for month {}",
+                        cell.account_id, cell.month
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Inserts or overwrites the entry for a sheet, account and month in one statement
+    async fn upsert_row(
+        conn: &mut SqliteConnection,
+        balance_sheet_id: &str,
+        account_id: &str,
+        month: i32,
+        amount: f64,
+        provenance: &EntryProvenance,
+    ) -> Result<Entry, AppError> {
+        sqlx::query_as::<_, Entry>(
+            "INSERT INTO entries (id, balance_sheet_id, account_id, month, amount, updated_at, status, source, note)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (balance_sheet_id, account_id, month) DO UPDATE SET
+                 amount = excluded.amount,
+                 updated_at = excluded.updated_at,
+                 status = excluded.status,
+                 source = excluded.source,
+                 note = excluded.note
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(balance_sheet_id)
+        .bind(account_id)
         .bind(month)
-        .fetch_optional(pool)
+        .bind(amount)
+        .bind(chrono::Utc::now())
+        .bind(&provenance.status)
+        .bind(&provenance.source)
+        .bind(&provenance.note)
+        .fetch_one(conn)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::from)
+    }
 
+    pub async fn create_with_executor<'e, E>(
+        executor: E,
+        balance_sheet_id: &str,
+        account_id: &str,
+        month: i32,
+        amount: f64,
+        provenance: &EntryProvenance,
+    ) -> Result<Entry, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now();
+        sqlx::query_as::<_, Entry>(
+            "INSERT INTO entries (id, balance_sheet_id, account_id, month, amount, updated_at, status, source, note) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING *"
+        )
+        .bind(id)
+        .bind(balance_sheet_id)
+        .bind(account_id)
+        .bind(month)
+        .bind(amount)
+        .bind(now)
+        .bind(&provenance.status)
+        .bind(&provenance.source)
+        .bind(&provenance.note)
+        .fetch_one(executor)
+        .await
+        .map_err(AppError::from)
+    }
 
-        if let Some(entry) = existing {
-            sqlx::query_as::<_, Entry>(
-                "UPDATE entries SET amount = ?, updated_at = ? WHERE id = ? RETURNING *",
+    // Marks estimated entries in a balance sheet (or one month of it) as confirmed
+    pub async fn confirm_estimated(
+        pool: &SqlitePool,
+        balance_sheet_id: String,
+        month: Option<u32>,
+    ) -> Result<u64, AppError> {
+        let mut tx = pool.begin().await?;
+        let previous = sqlx::query_as::<_, Entry>(
+            "SELECT * FROM entries WHERE balance_sheet_id = $1 AND ($2 IS NULL OR month = $2) AND status IN ($3, $4)",
+        )
+        .bind(&balance_sheet_id)
+        .bind(month)
+        .bind(ENTRY_STATUS_ESTIMATED)
+        .bind(ENTRY_STATUS_CARRIED_FORWARD)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let now = chrono::Utc::now();
+        for old in &previous {
+            let entry = sqlx::query_as::<_, Entry>(
+                "UPDATE entries SET status = ?, updated_at = ? WHERE id = ? RETURNING *",
             )
-            .bind(amount)
+            .bind(ENTRY_STATUS_CONFIRMED)
             .bind(now)
-            .bind(entry.id)
-            .fetch_one(pool)
-            .await
-            .map_err(|e| e.to_string())
-        } else {
-            let id = Uuid::new_v4().to_string();
-            sqlx::query_as::<_, Entry>(
-                "INSERT INTO entries (id, balance_sheet_id, account_id, month, amount, updated_at) VALUES (?, ?, ?, ?, ?, ?) RETURNING *"
+            .bind(&old.id)
+            .fetch_one(&mut *tx)
+            .await?;
+            AuditService::record_entry(&mut tx, Some(old), Some(&entry)).await?;
+        }
+        tx.commit().await?;
+
+        Ok(previous.len() as u64)
+    }
+
+    /**
+     * Drafts entries from every active recurring rule for one month of a balance sheet,
+     * or for the whole year when `month` is None. Each month builds on the previous month's
+     * balance, preferring a stored entry over a draft, and reaching into the previous
+     * year's December for January.
+     */
+    pub async fn preview_recurring(
+        pool: &SqlitePool,
+        balance_sheet_id: &str,
+        month: Option<u32>,
+    ) -> Result<Vec<DraftEntry>, AppError> {
+        let months: Vec<u32> = match month {
+            Some(m) if (1..=12).contains(&m) => vec![m],
+            Some(m) => {
+                return Err(AppError::validation("month", format!("Invalid month: {m}")));
+            }
+            None => (1..=12).collect(),
+        };
+        let sheet = BalanceSheetService::get_by_id(pool, balance_sheet_id.to_string())
+            .await?
+            .ok_or_else(|| AppError::not_found("BalanceSheet", balance_sheet_id))?;
+
+        // Archived accounts get no drafts, same as when a sheet carries balances forward
+        let archived: HashSet<String> = AccountService::get_all(pool, true)
+            .await?
+            .into_iter()
+            .filter(|account| account.is_archived)
+            .map(|account| account.id)
+            .collect();
+        let rules: Vec<_> = RecurringRuleService::get_all(pool)
+            .await?
+            .into_iter()
+            .filter(|rule| rule.is_active && !archived.contains(&rule.account_id))
+            .collect();
+
+        let stored: Vec<(String, i32, u32, f64)> = sqlx::query_as(
+            "SELECT e.account_id, b.year, e.month, e.amount FROM entries e JOIN balance_sheets b ON b.id = e.balance_sheet_id WHERE b.year IN (?, ?) AND b.deleted_at IS NULL",
+        )
+        .bind(sheet.year - 1)
+        .bind(sheet.year)
+        .fetch_all(pool)
+        .await?;
+        let stored: HashMap<(String, i32, u32), f64> = stored
+            .into_iter()
+            .map(|(account_id, year, month, amount)| ((account_id, year, month), amount))
+            .collect();
+        let stored_amount = |account_id: &str, year: i32, month: u32| {
+            stored.get(&(account_id.to_string(), year, month)).copied()
+        };
+
+        let mut drafts = Vec::new();
+        for rule in &rules {
+            let first = months[0];
+            let mut previous = if first == 1 {
+                stored_amount(&rule.account_id, sheet.year - 1, 12)
+            } else {
+                stored_amount(&rule.account_id, sheet.year, first - 1)
+            };
+
+            for &m in &months {
+                let existing_amount = stored_amount(&rule.account_id, sheet.year, m);
+                let draft = RecurringRuleService::next_amount(rule, previous);
+                if let Some(amount) = draft {
+                    drafts.push(DraftEntry {
+                        account_id: rule.account_id.clone(),
+                        rule_id: rule.id.clone(),
+                        month: m,
+                        amount,
+                        existing_amount,
+                    });
+                }
+                previous = existing_amount.or(draft);
+            }
+        }
+
+        drafts.sort_by(|a, b| a.month.cmp(&b.month).then(a.account_id.cmp(&b.account_id)));
+        Ok(drafts)
+    }
+
+    /**
+     * Writes the drafts from `preview_recurring` in a single transaction.
+     * Months that already have an entry are left alone unless `overwrite` is set.
+     */
+    pub async fn apply_recurring(
+        pool: &SqlitePool,
+        balance_sheet_id: String,
+        month: Option<u32>,
+        overwrite: bool,
+    ) -> Result<Vec<Entry>, AppError> {
+        let drafts = Self::preview_recurring(pool, &balance_sheet_id, month).await?;
+
+        let mut tx = pool.begin().await?;
+        let mut entries = Vec::new();
+        for draft in drafts {
+            if draft.existing_amount.is_some() && !overwrite {
+                continue;
+            }
+            let entry = Self::upsert_in_tx(
+                &mut tx,
+                balance_sheet_id.clone(),
+                draft.account_id,
+                draft.month as i32,
+                draft.amount,
+                EntryProvenance::new(ENTRY_STATUS_ESTIMATED, Some(ENTRY_SOURCE_RECURRING_RULE)),
             )
-            .bind(id)
-            .bind(balance_sheet_id)
-            .bind(account_id)
-            .bind(month)
-            .bind(amount)
-            .bind(now)
-            .fetch_one(pool)
-            .await
-            .map_err(|e| e.to_string())
+            .await?;
+            entries.push(entry);
         }
+        tx.commit().await?;
+
+        Ok(entries)
     }
 
     // DELETE
-    #[allow(dead_code)]
-    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), String> {
-        sqlx::query("DELETE FROM entries WHERE id = ?")
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let existing = sqlx::query_as::<_, Entry>("DELETE FROM entries WHERE id = ? RETURNING *")
             .bind(id)
-            .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .fetch_optional(&mut *tx)
+            .await?;
+        AuditService::record_entry(&mut tx, existing.as_ref(), None).await?;
+        tx.commit().await?;
         Ok(())
     }
+
+    // DELETE every account's entry for one month of a sheet
+    pub async fn clear_month(
+        pool: &SqlitePool,
+        balance_sheet_id: String,
+        month: u32,
+    ) -> Result<u64, AppError> {
+        if !(1..=12).contains(&month) {
+            return Err(AppError::validation(
+                "month",
+                format!("Invalid month: {month}"),
+            ));
+        }
+        Self::clear(pool, &balance_sheet_id, None, Some(month)).await
+    }
+
+    // DELETE every entry of one account in a sheet
+    pub async fn clear_account_year(
+        pool: &SqlitePool,
+        balance_sheet_id: String,
+        account_id: String,
+    ) -> Result<u64, AppError> {
+        Self::clear(pool, &balance_sheet_id, Some(&account_id), None).await
+    }
+
+    // Cleared cells have no entry at all, so they read as "no data" rather than a zero balance
+    async fn clear(
+        pool: &SqlitePool,
+        balance_sheet_id: &str,
+        account_id: Option<&str>,
+        month: Option<u32>,
+    ) -> Result<u64, AppError> {
+        let mut tx = pool.begin().await?;
+        let removed = sqlx::query_as::<_, Entry>(
+            "DELETE FROM entries WHERE balance_sheet_id = $1 AND ($2 IS NULL OR account_id = $2) AND ($3 IS NULL OR month = $3) RETURNING *",
+        )
+        .bind(balance_sheet_id)
+        .bind(account_id)
+        .bind(month)
+        .fetch_all(&mut *tx)
+        .await?;
+        for entry in &removed {
+            AuditService::record_entry(&mut tx, Some(entry), None).await?;
+        }
+        tx.commit().await?;
+        Ok(removed.len() as u64)
+    }
 }
 
 #[cfg(test)]
@@ -116,20 +621,34 @@ mod tests {
                 .expect("setup acct");
 
         // 1. Upsert (Create)
-        let entry = EntryService::upsert(&pool, sheet.id.clone(), account.id.clone(), 1, 100.0)
-            .await
-            .expect("created");
+        let entry = EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            account.id.clone(),
+            1,
+            100.0,
+            Default::default(),
+        )
+        .await
+        .expect("created");
         assert_eq!(entry.amount, 100.0);
 
         // 2. Upsert (Update - Key based)
-        let updated = EntryService::upsert(&pool, sheet.id.clone(), account.id.clone(), 1, 200.0)
-            .await
-            .expect("updated");
+        let updated = EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            account.id.clone(),
+            1,
+            200.0,
+            Default::default(),
+        )
+        .await
+        .expect("updated");
         assert_eq!(updated.amount, 200.0);
         assert_eq!(updated.id, entry.id); // Same entry
 
         // 3. Get All / By Sheet
-        let list = EntryService::get_by_balance_sheet(&pool, sheet.id.clone())
+        let list = EntryService::get_by_balance_sheet(&pool, sheet.id.clone(), None)
             .await
             .expect("list");
         assert_eq!(list.len(), 1);
@@ -142,5 +661,410 @@ mod tests {
             .await
             .expect("check");
         assert!(check.is_none());
+
+        // 5. Cells the grid would reject are rejected here too
+        for (month, amount) in [(13, 1.0), (0, 1.0), (2, f64::NAN)] {
+            let invalid = EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account.id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await;
+            assert!(matches!(invalid, Err(AppError::Validation { .. })));
+        }
+        AccountService::delete(&pool, account.id.clone())
+            .await
+            .expect("trash account");
+        let trashed = EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            account.id,
+            2,
+            1.0,
+            Default::default(),
+        )
+        .await;
+        assert!(matches!(trashed, Err(AppError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_keeps_one_entry_per_key() {
+        let pool = setup_test_db().await;
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet");
+        let account =
+            AccountService::upsert(&pool, None, "A".into(), "Asset".into(), "NZD".into(), None)
+                .await
+                .expect("account");
+
+        for amount in [100.0, 200.0, 300.0] {
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account.id.clone(),
+                4,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("save");
+        }
+        let entries = EntryService::get_by_balance_sheet(&pool, sheet.id.clone(), None)
+            .await
+            .expect("entries");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, 300.0);
+
+        // The unique index rejects a second row for the same key outright
+        let duplicate = EntryService::create_with_executor(
+            &pool,
+            &sheet.id,
+            &account.id,
+            4,
+            1.0,
+            &EntryProvenance::default(),
+        )
+        .await;
+        assert!(matches!(duplicate, Err(AppError::Conflict { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_clear_month_and_account_year() {
+        let pool = setup_test_db().await;
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet");
+        let mut accounts = Vec::new();
+        for name in ["A", "B"] {
+            let account = AccountService::upsert(
+                &pool,
+                None,
+                name.into(),
+                "Asset".into(),
+                "NZD".into(),
+                None,
+            )
+            .await
+            .expect("account");
+            accounts.push(account.id);
+        }
+        let cells = accounts
+            .iter()
+            .flat_map(|account_id| {
+                (1..=3).map(|month| EntryCell {
+                    account_id: account_id.clone(),
+                    month,
+                    amount: 10.0,
+                    provenance: None,
+                })
+            })
+            .collect();
+        EntryService::upsert_many(&pool, sheet.id.clone(), cells)
+            .await
+            .expect("grid");
+
+        let cleared = EntryService::clear_month(&pool, sheet.id.clone(), 2)
+            .await
+            .expect("clear month");
+        assert_eq!(cleared, 2);
+        let cleared =
+            EntryService::clear_account_year(&pool, sheet.id.clone(), accounts[0].clone())
+                .await
+                .expect("clear account");
+        assert_eq!(cleared, 2);
+
+        let left = EntryService::get_by_balance_sheet(&pool, sheet.id.clone(), None)
+            .await
+            .expect("entries");
+        assert_eq!(left.len(), 2);
+        assert!(left
+            .iter()
+            .all(|entry| entry.account_id == accounts[1] && entry.month != 2));
+
+        let invalid = EntryService::clear_month(&pool, sheet.id, 0).await;
+        assert!(matches!(invalid, Err(AppError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_unique_key_migration_keeps_latest_duplicate() {
+        let pool = setup_test_db().await;
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet");
+        let account =
+            AccountService::upsert(&pool, None, "A".into(), "Asset".into(), "NZD".into(), None)
+                .await
+                .expect("account");
+
+        // Back to the schema before the migration, with duplicates written by racing saves
+        sqlx::query("DROP INDEX idx_entries_sheet_account_month")
+            .execute(&pool)
+            .await
+            .expect("drop index");
+        for (id, month, amount, updated_at) in [
+            ("old", 5, 100.0, "2025-05-01T00:00:00Z"),
+            ("latest", 5, 300.0, "2025-05-03T00:00:00Z"),
+            ("middle", 5, 200.0, "2025-05-02T00:00:00Z"),
+            ("single", 6, 50.0, "2025-06-01T00:00:00Z"),
+        ] {
+            sqlx::query(
+                "INSERT INTO entries (id, balance_sheet_id, account_id, month, amount, updated_at, status) VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(&sheet.id)
+            .bind(&account.id)
+            .bind(month)
+            .bind(amount)
+            .bind(updated_at)
+            .bind(ENTRY_STATUS_CONFIRMED)
+            .execute(&pool)
+            .await
+            .expect("seed duplicate");
+        }
+
+        sqlx::raw_sql(include_str!(
+            "../../migrations/20260212000000_add_entries_unique_key.sql"
+        ))
+        .execute(&pool)
+        .await
+        .expect("run migration");
+
+        let mut ids: Vec<String> = EntryService::get_by_balance_sheet(&pool, sheet.id, None)
+            .await
+            .expect("entries")
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["latest".to_string(), "single".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_many_saves_a_grid_atomically() {
+        let pool = setup_test_db().await;
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet");
+        let account =
+            AccountService::upsert(&pool, None, "A".into(), "Asset".into(), "NZD".into(), None)
+                .await
+                .expect("account");
+        let existing = EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            account.id.clone(),
+            1,
+            100.0,
+            EntryProvenance {
+                note: Some("From the statement".into()),
+                ..EntryProvenance::new(ENTRY_STATUS_ESTIMATED, Some("bank"))
+            },
+        )
+        .await
+        .expect("existing");
+
+        let cell = |month: u32, amount: f64| EntryCell {
+            account_id: account.id.clone(),
+            month,
+            amount,
+            provenance: None,
+        };
+        let saved = EntryService::upsert_many(
+            &pool,
+            sheet.id.clone(),
+            vec![cell(1, 150.0), cell(2, 175.0)],
+        )
+        .await
+        .expect("save grid");
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].id, existing.id);
+        assert_eq!(saved[0].amount, 150.0);
+        // Without a provenance the stored one is kept
+        assert_eq!(saved[0].status, ENTRY_STATUS_ESTIMATED);
+        assert_eq!(saved[0].source.as_deref(), Some("bank"));
+        assert_eq!(saved[0].note.as_deref(), Some("From the statement"));
+        assert_eq!(saved[1].status, ENTRY_STATUS_CONFIRMED);
+
+        // Unchanged cells are skipped; a given provenance replaces the stored one
+        let saved = EntryService::upsert_many(
+            &pool,
+            sheet.id.clone(),
+            vec![
+                cell(1, 150.0),
+                EntryCell {
+                    provenance: Some(EntryProvenance::default()),
+                    ..cell(2, 175.0)
+                },
+            ],
+        )
+        .await
+        .expect("save grid again");
+        let january = AuditService::get_entry_history(&pool, account.id.clone(), 2025, 1)
+            .await
+            .expect("history");
+        assert_eq!(january.len(), 2);
+        assert_eq!(saved[0].status, ENTRY_STATUS_ESTIMATED);
+        assert_eq!(saved[1].status, ENTRY_STATUS_CONFIRMED);
+
+        // One bad cell rejects the whole batch
+        let invalid =
+            EntryService::upsert_many(&pool, sheet.id.clone(), vec![cell(3, 1.0), cell(13, 1.0)])
+                .await;
+        assert!(matches!(invalid, Err(AppError::Validation { .. })));
+        let duplicated =
+            EntryService::upsert_many(&pool, sheet.id.clone(), vec![cell(3, 1.0), cell(3, 2.0)])
+                .await;
+        assert!(matches!(duplicated, Err(AppError::Validation { .. })));
+
+        let entries = EntryService::get_by_balance_sheet(&pool, sheet.id, None)
+            .await
+            .expect("entries");
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_recurring_rules_preview_and_apply() {
+        use crate::services::recurring_rule::{
+            RecurringRuleInput, RULE_TYPE_AMORTIZATION, RULE_TYPE_FIXED_AMOUNT,
+        };
+
+        let pool = setup_test_db().await;
+        let previous = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("2024");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("2025");
+        let mortgage = AccountService::upsert(
+            &pool,
+            None,
+            "Mortgage".into(),
+            "Liability".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("mortgage");
+        let car = AccountService::upsert(
+            &pool,
+            None,
+            "Car".into(),
+            "Asset".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("car");
+
+        RecurringRuleService::upsert(
+            &pool,
+            None,
+            RecurringRuleInput {
+                account_id: mortgage.id.clone(),
+                rule_type: RULE_TYPE_AMORTIZATION.into(),
+                amount: 2000.0,
+                rate: 0.06,
+                is_active: true,
+            },
+        )
+        .await
+        .expect("mortgage rule");
+        RecurringRuleService::upsert(
+            &pool,
+            None,
+            RecurringRuleInput {
+                account_id: car.id.clone(),
+                rule_type: RULE_TYPE_FIXED_AMOUNT.into(),
+                amount: 15000.0,
+                rate: 0.0,
+                is_active: true,
+            },
+        )
+        .await
+        .expect("car rule");
+
+        EntryService::upsert(
+            &pool,
+            previous.id.clone(),
+            mortgage.id.clone(),
+            12,
+            300_000.0,
+            Default::default(),
+        )
+        .await
+        .expect("december");
+        // A real February balance takes precedence over the draft and seeds March
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            mortgage.id.clone(),
+            2,
+            298_000.0,
+            Default::default(),
+        )
+        .await
+        .expect("february");
+
+        let drafts = EntryService::preview_recurring(&pool, &sheet.id, None)
+            .await
+            .expect("preview");
+        assert_eq!(drafts.len(), 24);
+        let mortgage_drafts: Vec<&DraftEntry> = drafts
+            .iter()
+            .filter(|d| d.account_id == mortgage.id)
+            .collect();
+        assert_eq!(mortgage_drafts[0].amount, 299_500.0);
+        assert_eq!(mortgage_drafts[1].existing_amount, Some(298_000.0));
+        assert_eq!(mortgage_drafts[2].amount, 297_490.0);
+
+        let written = EntryService::apply_recurring(&pool, sheet.id.clone(), None, false)
+            .await
+            .expect("apply");
+        assert_eq!(written.len(), 23);
+        let february = EntryService::get_by_key(&pool, &sheet.id, &mortgage.id, 2)
+            .await
+            .expect("get")
+            .expect("february exists");
+        assert_eq!(february.amount, 298_000.0);
+
+        let rewritten = EntryService::apply_recurring(&pool, sheet.id.clone(), Some(2), true)
+            .await
+            .expect("overwrite");
+        assert_eq!(rewritten.len(), 2);
+
+        // Archived accounts are left out of both the preview and the apply
+        AccountService::toggle_archive(&pool, car.id.clone())
+            .await
+            .expect("archive car");
+        let drafts = EntryService::preview_recurring(&pool, &sheet.id, Some(3))
+            .await
+            .expect("preview without car");
+        assert!(drafts.iter().all(|d| d.account_id == mortgage.id));
+        let rewritten = EntryService::apply_recurring(&pool, sheet.id.clone(), Some(3), true)
+            .await
+            .expect("overwrite without car");
+        assert_eq!(rewritten.len(), 1);
+
+        // A trashed sheet no longer supplies January's opening balance
+        BalanceSheetService::delete(&pool, previous.id)
+            .await
+            .expect("trash 2024");
+        let drafts = EntryService::preview_recurring(&pool, &sheet.id, Some(1))
+            .await
+            .expect("preview without 2024");
+        assert!(drafts.is_empty());
+
+        // Nor does a trashed account's rule produce drafts
+        AccountService::delete(&pool, mortgage.id)
+            .await
+            .expect("trash mortgage");
+        let drafts = EntryService::preview_recurring(&pool, &sheet.id, Some(4))
+            .await
+            .expect("preview without mortgage");
+        assert!(drafts.is_empty());
     }
 }
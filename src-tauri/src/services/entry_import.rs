@@ -0,0 +1,330 @@
+use crate::error::AppError;
+use crate::models::Account;
+use crate::services::account::AccountService;
+use crate::services::balance_sheet::BalanceSheetService;
+use crate::services::csv_import::{CsvRow, ImportReport, ImportRowStatus};
+use crate::services::entry::{
+    EntryProvenance, EntryService, ENTRY_SOURCE_CSV_IMPORT, ENTRY_STATUS_IMPORTED,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryImportReport {
+    #[serde(flatten)]
+    pub summary: ImportReport<EntryCsvRow>,
+    pub created_balance_sheet_years: Vec<i32>,
+}
+
+/**
+ * A single CSV row. Headers are matched case-insensitively:
+ * account,year,month,amount[,currency]
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryCsvRow {
+    #[serde(rename(serialize = "accountName"), alias = "account_name")]
+    pub account: String,
+    pub year: i32,
+    pub month: u32,
+    pub amount: f64,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+impl CsvRow for EntryCsvRow {
+    fn validate(&mut self) -> Result<(), AppError> {
+        if self.account.is_empty() {
+            return Err(AppError::validation("account", "Account name is required"));
+        }
+        if !(1..=12).contains(&self.month) {
+            return Err(AppError::validation(
+                "month",
+                format!("Invalid month: {}", self.month),
+            ));
+        }
+        if !self.amount.is_finite() {
+            return Err(AppError::validation(
+                "amount",
+                format!("Invalid amount: {}", self.amount),
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct EntryImportService;
+
+impl EntryImportService {
+    /**
+     * Upserts every valid row in a single transaction, creating missing balance sheets.
+     * Row-level problems are reported and do not abort the import; database errors do.
+     */
+    pub async fn import_csv(
+        pool: &SqlitePool,
+        contents: &str,
+        dry_run: bool,
+    ) -> Result<EntryImportReport, AppError> {
+        let mut report = ImportReport::<EntryCsvRow>::new(dry_run);
+        let rows = report.read_rows(contents)?;
+        let mut created_balance_sheet_years = Vec::new();
+
+        let mut accounts: HashMap<String, Option<Account>> = HashMap::new();
+        // Year -> sheet id, or None when that year's sheet is in the trash
+        let mut sheets: HashMap<i32, Option<String>> = HashMap::new();
+
+        let mut tx = pool.begin().await?;
+
+        for (line, row) in rows {
+            if !accounts.contains_key(&row.account) {
+                let account = AccountService::get_by_name(&mut *tx, row.account.clone()).await?;
+                accounts.insert(row.account.clone(), account);
+            }
+            let account = match accounts.get(&row.account) {
+                Some(Some(account)) => account,
+                _ => {
+                    report.push_error(
+                        line,
+                        Some(&row),
+                        format!("Account '{}' not found", row.account),
+                    );
+                    continue;
+                }
+            };
+            if account.deleted_at.is_some() {
+                report.push_error(
+                    line,
+                    Some(&row),
+                    format!("Account '{}' is in the trash", row.account),
+                );
+                continue;
+            }
+
+            if let Some(currency) = &row.currency {
+                if !currency.eq_ignore_ascii_case(&account.currency) {
+                    report.push_error(
+                        line,
+                        Some(&row),
+                        format!(
+                            "Currency {currency} does not match account currency {}",
+                            account.currency
+                        ),
+                    );
+                    continue;
+                }
+            }
+
+            if let Entry::Vacant(slot) = sheets.entry(row.year) {
+                let sheet = match BalanceSheetService::get_by_year(&mut *tx, row.year).await? {
+                    Some(sheet) => sheet,
+                    None => {
+                        created_balance_sheet_years.push(row.year);
+                        BalanceSheetService::create_with_executor(&mut *tx, row.year).await?
+                    }
+                };
+                slot.insert(sheet.deleted_at.is_none().then_some(sheet.id));
+            }
+            let Some(Some(sheet_id)) = sheets.get(&row.year).cloned() else {
+                report.push_error(
+                    line,
+                    Some(&row),
+                    format!("Balance sheet {} is in the trash", row.year),
+                );
+                continue;
+            };
+            let month = row.month as i32;
+
+            let existing =
+                EntryService::get_by_key(&mut *tx, &sheet_id, &account.id, month).await?;
+            let status = match existing {
+                Some(entry) if entry.amount == row.amount => ImportRowStatus::Skipped,
+                Some(_) => ImportRowStatus::Updated,
+                None => ImportRowStatus::Created,
+            };
+
+            if status != ImportRowStatus::Skipped {
+                EntryService::upsert_in_tx(
+                    &mut tx,
+                    sheet_id,
+                    account.id.clone(),
+                    month,
+                    row.amount,
+                    EntryProvenance::new(ENTRY_STATUS_IMPORTED, Some(ENTRY_SOURCE_CSV_IMPORT)),
+                )
+                .await?;
+            }
+
+            report.push(line, Some(&row), status, None);
+        }
+
+        report.finish(tx).await?;
+
+        Ok(EntryImportReport {
+            summary: report,
+            created_balance_sheet_years,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    async fn setup_accounts(pool: &SqlitePool) {
+        AccountService::upsert(
+            pool,
+            None,
+            "Checking".into(),
+            "Asset".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("checking");
+        AccountService::upsert(
+            pool,
+            None,
+            "Brokerage".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("brokerage");
+    }
+
+    #[tokio::test]
+    async fn test_import_creates_sheets_and_entries() {
+        let pool = setup_test_db().await;
+        setup_accounts(&pool).await;
+
+        let csv = "Account,Year,Month,Amount,Currency\n\
+                   Checking,2023,1,1000.50,NZD\n\
+                   Checking,2023,2,1100,\n\
+                   Brokerage,2024,1,5000,USD\n";
+
+        let report = EntryImportService::import_csv(&pool, csv, false)
+            .await
+            .expect("import");
+
+        assert_eq!(report.summary.created, 3);
+        assert_eq!(report.summary.errored, 0);
+        assert_eq!(report.created_balance_sheet_years, vec![2023, 2024]);
+
+        let sheet = BalanceSheetService::get_by_year(&pool, 2023)
+            .await
+            .expect("sheet")
+            .expect("sheet 2023 created");
+        let entries = EntryService::get_by_balance_sheet(&pool, sheet.id, None)
+            .await
+            .expect("entries");
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_import_reports_updated_skipped_and_errors() {
+        let pool = setup_test_db().await;
+        setup_accounts(&pool).await;
+
+        EntryImportService::import_csv(
+            &pool,
+            "account,year,month,amount\nChecking,2023,1,100\nChecking,2023,2,200\n",
+            false,
+        )
+        .await
+        .expect("initial import");
+
+        let csv = "account,year,month,amount,currency\n\
+                   Checking,2023,1,150,\n\
+                   Checking,2023,2,200,\n\
+                   Unknown,2023,1,10,\n\
+                   Checking,2023,13,10,\n\
+                   Brokerage,2023,1,10,EUR\n\
+                   Checking,2023,3,not-a-number,\n";
+
+        let report = EntryImportService::import_csv(&pool, csv, false)
+            .await
+            .expect("import");
+
+        assert_eq!(report.summary.updated, 1);
+        assert_eq!(report.summary.skipped, 1);
+        assert_eq!(report.summary.errored, 4);
+        assert_eq!(report.summary.rows.len(), 6);
+        assert_eq!(report.summary.rows[0].status, ImportRowStatus::Updated);
+        assert_eq!(report.summary.rows[1].status, ImportRowStatus::Skipped);
+        assert_eq!(
+            report.summary.rows[2].message.as_deref(),
+            Some("Account 'Unknown' not found")
+        );
+        assert_eq!(report.summary.rows[3].line, 5);
+    }
+
+    #[tokio::test]
+    async fn test_import_dry_run_does_not_persist() {
+        let pool = setup_test_db().await;
+        setup_accounts(&pool).await;
+
+        let report = EntryImportService::import_csv(
+            &pool,
+            "account,year,month,amount\nChecking,2023,1,100\n",
+            true,
+        )
+        .await
+        .expect("dry run");
+
+        assert!(report.summary.dry_run);
+        assert_eq!(report.summary.created, 1);
+        assert_eq!(report.created_balance_sheet_years, vec![2023]);
+
+        let sheets = BalanceSheetService::get_all(&pool).await.expect("sheets");
+        assert!(sheets.is_empty());
+        let entries = EntryService::get_all(&pool).await.expect("entries");
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_trashed_accounts_and_years() {
+        let pool = setup_test_db().await;
+        setup_accounts(&pool).await;
+        let brokerage = AccountService::get_by_name(&pool, "Brokerage".into())
+            .await
+            .expect("lookup")
+            .expect("brokerage");
+        AccountService::delete(&pool, brokerage.id)
+            .await
+            .expect("trash brokerage");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2022)
+            .await
+            .expect("sheet");
+        BalanceSheetService::delete(&pool, sheet.id)
+            .await
+            .expect("trash 2022");
+
+        let csv = "account,year,month,amount
+                   Brokerage,2023,1,10
+                   Checking,2022,1,20
+                   Checking,2023,1,30
+";
+        let report = EntryImportService::import_csv(&pool, csv, false)
+            .await
+            .expect("import");
+
+        assert_eq!(report.summary.created, 1);
+        assert_eq!(report.summary.errored, 2);
+        assert_eq!(
+            report.summary.rows[0].message.as_deref(),
+            Some("Account 'Brokerage' is in the trash")
+        );
+        assert_eq!(
+            report.summary.rows[1].message.as_deref(),
+            Some("Balance sheet 2022 is in the trash")
+        );
+        assert_eq!(
+            EntryService::get_all(&pool).await.expect("entries").len(),
+            1
+        );
+    }
+}
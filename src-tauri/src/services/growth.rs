@@ -0,0 +1,316 @@
+use crate::error::AppError;
+use crate::services::net_worth::{NetWorthDataPoint, NetWorthQuery, NetWorthService};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/**
+ * A change in net worth between two months. `percentage` is relative to the absolute
+ * starting value (0-100 scale) and is None when the start is zero.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrowthChange {
+    pub absolute: f64,
+    pub percentage: Option<f64>,
+}
+
+impl GrowthChange {
+    fn between(previous: f64, current: f64) -> Self {
+        let absolute = current - previous;
+        let percentage = if previous == 0.0 {
+            None
+        } else {
+            Some(absolute / previous.abs() * 100.0)
+        };
+        Self {
+            absolute,
+            percentage,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrowthPoint {
+    pub year: i32,
+    pub month: u32,
+    pub net_worth: f64,
+    // Against the previous calendar month; None when that month has no data
+    pub month_over_month: Option<GrowthChange>,
+    // Against the same month a year earlier; None when that month has no data
+    pub rolling_12_months: Option<GrowthChange>,
+    // How far below the highest net worth seen so far (0 at a new peak)
+    pub drawdown: GrowthChange,
+}
+
+// Change from the last month with data in the previous year to the last month with data in this one
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearlyGrowth {
+    pub year: i32,
+    pub start_net_worth: f64,
+    pub end_net_worth: f64,
+    pub change: GrowthChange,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthChange {
+    pub year: i32,
+    pub month: u32,
+    pub change: GrowthChange,
+}
+
+// The largest fall from a peak to a later trough
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Drawdown {
+    pub peak_year: i32,
+    pub peak_month: u32,
+    pub trough_year: i32,
+    pub trough_month: u32,
+    pub change: GrowthChange,
+}
+
+/**
+ * Growth analytics over the net worth history, in the home currency.
+ * `cagr` is the compound annual growth rate (0-100 scale) between the first and last months,
+ * and is None when either end isn't positive or they're the same month.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrowthReport {
+    pub currency: Option<String>,
+    pub points: Vec<GrowthPoint>,
+    pub year_over_year: Vec<YearlyGrowth>,
+    pub cagr: Option<f64>,
+    pub best_month: Option<MonthChange>,
+    pub worst_month: Option<MonthChange>,
+    pub max_drawdown: Option<Drawdown>,
+}
+
+pub struct GrowthService;
+
+impl GrowthService {
+    // Accepts the same filters as the net worth history, so a period bounds the CAGR
+    pub async fn get_report(
+        pool: &SqlitePool,
+        query: &NetWorthQuery,
+    ) -> Result<GrowthReport, AppError> {
+        let history = NetWorthService::query_history(pool, query).await?;
+        Ok(Self::build_report(&history))
+    }
+
+    fn build_report(history: &[NetWorthDataPoint]) -> GrowthReport {
+        // Months since year 0, so gaps between points are easy to spot
+        let period = |point: &NetWorthDataPoint| point.year as i64 * 12 + point.month as i64 - 1;
+        let find = |target: i64| history.iter().find(|point| period(point) == target);
+
+        let mut points = Vec::with_capacity(history.len());
+        let mut best_month: Option<MonthChange> = None;
+        let mut worst_month: Option<MonthChange> = None;
+        let mut max_drawdown: Option<Drawdown> = None;
+        let mut peak: Option<&NetWorthDataPoint> = None;
+
+        for point in history {
+            let month_over_month = find(period(point) - 1)
+                .map(|previous| GrowthChange::between(previous.net_worth, point.net_worth));
+            let rolling_12_months = find(period(point) - 12)
+                .map(|previous| GrowthChange::between(previous.net_worth, point.net_worth));
+
+            if let Some(change) = &month_over_month {
+                let month_change = || MonthChange {
+                    year: point.year,
+                    month: point.month,
+                    change: change.clone(),
+                };
+                if best_month
+                    .as_ref()
+                    .is_none_or(|best| change.absolute > best.change.absolute)
+                {
+                    best_month = Some(month_change());
+                }
+                if worst_month
+                    .as_ref()
+                    .is_none_or(|worst| change.absolute < worst.change.absolute)
+                {
+                    worst_month = Some(month_change());
+                }
+            }
+
+            let peak_point = match peak {
+                Some(peak_point) if peak_point.net_worth >= point.net_worth => peak_point,
+                _ => point,
+            };
+            peak = Some(peak_point);
+            let drawdown = GrowthChange::between(peak_point.net_worth, point.net_worth);
+            if drawdown.absolute < 0.0
+                && max_drawdown
+                    .as_ref()
+                    .is_none_or(|max| drawdown.absolute < max.change.absolute)
+            {
+                max_drawdown = Some(Drawdown {
+                    peak_year: peak_point.year,
+                    peak_month: peak_point.month,
+                    trough_year: point.year,
+                    trough_month: point.month,
+                    change: drawdown.clone(),
+                });
+            }
+
+            points.push(GrowthPoint {
+                year: point.year,
+                month: point.month,
+                net_worth: point.net_worth,
+                month_over_month,
+                rolling_12_months,
+                drawdown,
+            });
+        }
+
+        // The history is sorted, so the last point of each year closes it
+        let mut year_ends: Vec<&NetWorthDataPoint> = Vec::new();
+        for point in history {
+            match year_ends.last_mut() {
+                Some(last) if last.year == point.year => *last = point,
+                _ => year_ends.push(point),
+            }
+        }
+        let year_over_year = year_ends
+            .windows(2)
+            .filter(|pair| pair[1].year == pair[0].year + 1)
+            .map(|pair| YearlyGrowth {
+                year: pair[1].year,
+                start_net_worth: pair[0].net_worth,
+                end_net_worth: pair[1].net_worth,
+                change: GrowthChange::between(pair[0].net_worth, pair[1].net_worth),
+            })
+            .collect();
+
+        let cagr = match (history.first(), history.last()) {
+            (Some(first), Some(last)) => {
+                let months = period(last) - period(first);
+                if months > 0 && first.net_worth > 0.0 && last.net_worth > 0.0 {
+                    let years = months as f64 / 12.0;
+                    Some(((last.net_worth / first.net_worth).powf(1.0 / years) - 1.0) * 100.0)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        GrowthReport {
+            currency: history.first().map(|point| point.currency.clone()),
+            points,
+            year_over_year,
+            cagr,
+            best_month,
+            worst_month,
+            max_drawdown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        account::AccountService, balance_sheet::BalanceSheetService, entry::EntryService,
+        user_settings::UserSettingsService,
+    };
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn test_growth_report() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("cash");
+
+        let values = [
+            (2022, 12, 1000.0),
+            (2023, 1, 1200.0),
+            (2023, 2, 900.0),
+            (2023, 3, 1100.0),
+            // April has no data
+            (2023, 5, 1300.0),
+            (2023, 12, 1210.0),
+        ];
+        for (year, month, amount) in values {
+            let sheet = match BalanceSheetService::get_by_year(&pool, year)
+                .await
+                .expect("lookup sheet")
+            {
+                Some(sheet) => sheet,
+                None => BalanceSheetService::upsert(&pool, None, year)
+                    .await
+                    .expect("sheet"),
+            };
+            EntryService::upsert(
+                &pool,
+                sheet.id,
+                cash.id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("entry");
+        }
+
+        let report = GrowthService::get_report(&pool, &NetWorthQuery::default())
+            .await
+            .expect("report");
+        assert_eq!(report.currency.as_deref(), Some("USD"));
+        assert_eq!(report.points.len(), 6);
+
+        let january = &report.points[1];
+        assert_eq!(
+            january.month_over_month,
+            Some(GrowthChange {
+                absolute: 200.0,
+                percentage: Some(20.0)
+            })
+        );
+        // May follows a month with no data
+        assert_eq!(report.points[4].month_over_month, None);
+        assert_eq!(
+            report.points[5].rolling_12_months,
+            Some(GrowthChange {
+                absolute: 210.0,
+                percentage: Some(21.0)
+            })
+        );
+
+        let best = report.best_month.expect("best month");
+        assert_eq!((best.year, best.month), (2023, 1));
+        let worst = report.worst_month.expect("worst month");
+        assert_eq!((worst.year, worst.month), (2023, 2));
+        assert_eq!(worst.change.absolute, -300.0);
+
+        let drawdown = report.max_drawdown.expect("drawdown");
+        assert_eq!((drawdown.peak_year, drawdown.peak_month), (2023, 1));
+        assert_eq!((drawdown.trough_year, drawdown.trough_month), (2023, 2));
+        assert_eq!(drawdown.change.percentage, Some(-25.0));
+        assert_eq!(report.points[5].drawdown.absolute, -90.0);
+
+        assert_eq!(report.year_over_year.len(), 1);
+        assert_eq!(report.year_over_year[0].year, 2023);
+        assert_eq!(report.year_over_year[0].change.absolute, 210.0);
+
+        // Dec 2022 to Dec 2023 is exactly one year
+        let cagr = report.cagr.expect("cagr");
+        assert!((cagr - 21.0).abs() < 1e-9);
+    }
+}
@@ -1,10 +1,21 @@
 pub mod account;
+pub mod archive;
+pub mod audit;
+pub mod backup;
 pub mod balance_sheet;
+pub mod budget;
+pub mod csv_import;
 pub mod currency_rates;
 pub mod entry;
+pub mod entry_import;
+pub mod growth;
 pub mod net_worth;
 pub mod onboarding;
+pub mod recurring_rule;
 pub mod retirement;
 pub mod retirement_plan;
 pub mod retirement_plan_projection;
+pub mod savings;
+pub mod transaction;
+pub mod trash;
 pub mod user_settings;
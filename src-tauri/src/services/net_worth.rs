@@ -1,13 +1,42 @@
+use crate::error::AppError;
 use crate::{
-    models::{Account, BalanceSheet},
-    services::currency_rates::currency_rate::CurrencyRateService,
+    models::{Account, BalanceSheet, Entry},
+    services::{currency_rates::conversion::CurrencyConverter, entry::EntryProvenance},
 };
 use chrono::Datelike;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
-use std::{cmp::Ordering, collections::HashMap};
+use sqlx::SqlitePool;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+/**
+ * How the exchange rates behind a data point were resolved.
+ * Ordered from best to worst so a month reports its worst conversion.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateQuality {
+    // Every foreign amount had a rate for its own month
+    Exact,
+    // At least one amount was converted with the nearest earlier known rate
+    Estimated,
+    // At least one amount had no usable rate and was left out of the totals
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateWarning {
+    pub currency: String,
+    pub quality: RateQuality,
+    // Period of the rate that was used instead, when one was found
+    pub rate_year: Option<i32>,
+    pub rate_month: Option<u32>,
+}
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NetWorthDataPoint {
     pub year: i32,
@@ -16,113 +45,306 @@ pub struct NetWorthDataPoint {
     pub total_liabilities: f64,
     pub net_worth: f64,
     pub currency: String,
+    pub rate_quality: RateQuality,
+    pub warnings: Vec<RateWarning>,
+    // Share (0-1) of the month's assets plus liabilities that comes from estimated entries
+    pub estimated_share: f64,
+    // Active accounts with entries elsewhere in the year but none this month. A month with no
+    // entry is "no data" and left out of the totals, unlike an entry of zero.
+    pub missing_account_ids: Vec<String>,
+    // Change in net worth since the previous month split into balance movement and exchange rate
+    // movement. Only accounts with a converted entry in both months count; None for a month whose
+    // previous month has no data.
+    pub local_change: Option<f64>,
+    pub currency_effect: Option<f64>,
+    // The same split for each foreign-currency account, in the account's own sign
+    pub fx_attribution: Vec<FxAttribution>,
+}
+
+/**
+ * How much of a foreign-currency account's change in home-currency value came from its balance
+ * (valued at last month's rate) and how much from the rate moving (applied to this month's balance).
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FxAttribution {
+    pub account_id: String,
+    pub currency: String,
+    pub local_change: f64,
+    pub currency_effect: f64,
+}
+
+/**
+ * A month whose balances can't be fully converted into the home currency with rates from that month.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnconvertibleMonth {
+    pub year: i32,
+    pub month: u32,
+    pub home_currency: String,
+    // No rate path at all; balances in these currencies are left out of the totals
+    pub missing_currencies: Vec<String>,
+    // Converted with an older rate
+    pub estimated_currencies: Vec<String>,
 }
+
+/**
+ * Narrows the net worth history. Every filter is optional and an empty query returns
+ * the full history. The period is inclusive; a missing month means January for `from`
+ * and December for `to`.
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetWorthQuery {
+    pub from_year: Option<i32>,
+    pub from_month: Option<u32>,
+    pub to_year: Option<i32>,
+    pub to_month: Option<u32>,
+    pub account_ids: Option<Vec<String>>,
+    // 'Asset' or 'Liability'
+    pub account_type: Option<String>,
+    pub sub_category: Option<String>,
+}
+
+impl NetWorthQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        for (field, month) in [("fromMonth", self.from_month), ("toMonth", self.to_month)] {
+            if let Some(month) = month {
+                if !(1..=12).contains(&month) {
+                    return Err(AppError::validation(
+                        field,
+                        format!("Invalid month: {month}"),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Months since year 0, so periods compare as plain integers in SQL
+    fn start_period(&self) -> Option<i64> {
+        self.from_year
+            .map(|year| year as i64 * 12 + self.from_month.unwrap_or(1) as i64)
+    }
+
+    fn end_period(&self) -> Option<i64> {
+        self.to_year
+            .map(|year| year as i64 * 12 + self.to_month.unwrap_or(12) as i64)
+    }
+}
+
 struct MonthlyAgg {
     assets: f64,
     liabilities: f64,
+    // Converted absolute amounts of entries that aren't confirmed or imported
+    estimated: f64,
+    warnings: Vec<RateWarning>,
+    // Accounts with an entry this month, converted or not
+    account_ids: HashSet<String>,
+    // Account -> (balance in its own currency, rate to home, counts negative)
+    balances: HashMap<String, (f64, f64, bool)>,
 }
 
-pub struct NetWorthService;
+/**
+ * One month of net worth split by sub-category and by account, in the home currency.
+ * Amounts are as entered, so liabilities are positive like in `NetWorthDataPoint`.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetWorthBreakdown {
+    pub year: i32,
+    pub month: u32,
+    pub currency: String,
+    pub by_sub_category: Vec<SubCategoryTotal>,
+    pub by_account: Vec<AccountTotal>,
+    pub rate_quality: RateQuality,
+    pub warnings: Vec<RateWarning>,
+}
 
-impl NetWorthService {
-    pub async fn get_history(pool: &SqlitePool) -> Result<Vec<NetWorthDataPoint>, String> {
-        // 1. Get Home Currency
-        let settings = crate::services::user_settings::UserSettingsService::get_all(pool)
-            .await
-            .map_err(|e| e.to_string())?
-            .into_iter()
-            .next()
-            .ok_or("User settings not found")?;
-        let home_currency = settings.home_currency;
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubCategoryTotal {
+    pub account_type: String,
+    // None for accounts without a sub-category
+    pub sub_category: Option<String>,
+    pub total: f64,
+}
 
-        // 2. Fetch all required data
-        let entries = crate::services::entry::EntryService::get_all(pool).await?;
-        let accounts = crate::services::account::AccountService::get_all(pool, true).await?;
-        let sheets = crate::services::balance_sheet::BalanceSheetService::get_all(pool).await?;
-        let rates = CurrencyRateService::get_all(pool).await?;
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTotal {
+    pub account_id: String,
+    pub account_type: String,
+    pub sub_category: Option<String>,
+    pub total: f64,
+}
 
-        // 3. Build fast lookups
-        let account_map: HashMap<String, Account> =
-            accounts.into_iter().map(|a| (a.id.clone(), a)).collect();
+// An entry with its account details and its amount in the home currency
+struct ConvertedEntry {
+    year: i32,
+    month: u32,
+    account_id: String,
+    account_type: String,
+    sub_category: Option<String>,
+    sort_order: i32,
+    is_archived: bool,
+    currency: String,
+    status: String,
+    local_amount: f64,
+    // Rate into the home currency; None when no usable rate was found
+    rate: Option<f64>,
+    warning: Option<RateWarning>,
+}
 
-        let sheet_map: HashMap<String, BalanceSheet> =
-            sheets.into_iter().map(|s| (s.id.clone(), s)).collect();
+impl ConvertedEntry {
+    fn amount(&self) -> Option<f64> {
+        self.rate.map(|rate| self.local_amount * rate)
+    }
+}
 
-        // Key: (Year, Month, FromCurrency, ToCurrency) -> Rate
-        let mut rate_map: HashMap<(i32, u32, String, String), f64> = HashMap::new();
-        for r in rates {
-            rate_map.insert(
-                (
-                    r.year,
-                    r.month,
-                    r.from_currency.clone(),
-                    r.to_currency.clone(),
-                ),
-                r.rate,
-            );
-        }
+pub struct NetWorthService;
+
+impl NetWorthService {
+    pub async fn get_history(pool: &SqlitePool) -> Result<Vec<NetWorthDataPoint>, AppError> {
+        Self::query_history(pool, &NetWorthQuery::default()).await
+    }
 
-        // 4. Aggregate data
-        // specific struct to hold aggregated sums
+    pub async fn query_history(
+        pool: &SqlitePool,
+        query: &NetWorthQuery,
+    ) -> Result<Vec<NetWorthDataPoint>, AppError> {
+        let (home_currency, converted) = Self::load_converted(pool, query).await?;
 
         // Map (Year, Month) -> MonthlyAgg
         let mut agg_map: HashMap<(i32, u32), MonthlyAgg> = HashMap::new();
+        // Year -> active accounts with at least one entry that year
+        let mut year_accounts: HashMap<i32, HashSet<String>> = HashMap::new();
 
-        for entry in entries {
-            let sheet = sheet_map
-                .get(&entry.balance_sheet_id)
-                .ok_or("Balance sheet not found for entry")?;
-            let account = account_map
-                .get(&entry.account_id)
-                .ok_or("Account not found for entry")?;
+        // Account -> its currency, for the FX attribution
+        let currencies: HashMap<String, String> = converted
+            .iter()
+            .map(|item| (item.account_id.clone(), item.currency.clone()))
+            .collect();
 
-            let year = sheet.year;
-            let month = entry.month;
+        for item in converted {
+            let entry_agg = agg_map
+                .entry((item.year, item.month))
+                .or_insert(MonthlyAgg {
+                    assets: 0.0,
+                    liabilities: 0.0,
+                    estimated: 0.0,
+                    warnings: Vec::new(),
+                    account_ids: HashSet::new(),
+                    balances: HashMap::new(),
+                });
+            entry_agg.account_ids.insert(item.account_id.clone());
+            if !item.is_archived {
+                year_accounts
+                    .entry(item.year)
+                    .or_default()
+                    .insert(item.account_id.clone());
+            }
 
-            // Convert amount
-            let amount_in_home = if account.currency == home_currency {
-                entry.amount
-            } else {
-                // Find rate for explicit pair
-                let rate =
-                    rate_map.get(&(year, month, account.currency.clone(), home_currency.clone()));
+            if let Some(warning) = &item.warning {
+                if !entry_agg.warnings.contains(warning) {
+                    entry_agg.warnings.push(warning.clone());
+                }
+            }
 
-                let conversion_rate = rate.unwrap_or(&1.0);
-                entry.amount * conversion_rate
+            // Never guess 1:1 for a foreign currency; leave it out instead
+            let (Some(rate), Some(amount_in_home)) = (item.rate, item.amount()) else {
+                continue;
             };
+            entry_agg.balances.insert(
+                item.account_id.clone(),
+                (item.local_amount, rate, item.account_type != "Asset"),
+            );
 
-            let entry_agg = agg_map.entry((year, month)).or_insert(MonthlyAgg {
-                assets: 0.0,
-                liabilities: 0.0,
-            });
-
-            if account.account_type == "Asset" {
+            if EntryProvenance::is_estimated_status(&item.status) {
+                entry_agg.estimated += amount_in_home.abs();
+            }
+            if item.account_type == "Asset" {
                 entry_agg.assets += amount_in_home;
             } else {
                 entry_agg.liabilities += amount_in_home;
             }
         }
 
-        // 5. Convert to Result List
-        // 5. Filter future dates and Convert to Result List
-        let now = chrono::Local::now();
-        let current_year = now.year();
-        let current_month = now.month();
-
+        // Filter future dates and Convert to Result List
         let mut result: Vec<NetWorthDataPoint> = agg_map
-            .into_iter()
-            .filter(|((year, month), _)| match year.cmp(&current_year) {
-                Ordering::Less => true,
-                Ordering::Equal => *month <= current_month,
-                Ordering::Greater => false,
-            })
-            .map(|((year, month), agg)| NetWorthDataPoint {
-                year,
-                month,
-                total_assets: agg.assets,
-                total_liabilities: agg.liabilities,
-                net_worth: agg.assets - agg.liabilities,
-                currency: home_currency.clone(),
+            .iter()
+            .filter(|((year, month), _)| Self::is_past_or_current(*year, *month))
+            .map(|(&(year, month), agg)| {
+                let (previous_year, previous_month) = if month == 1 {
+                    (year - 1, 12)
+                } else {
+                    (year, month - 1)
+                };
+                let mut local_change = None;
+                let mut currency_effect = None;
+                let mut fx_attribution = Vec::new();
+                if let Some(previous) = agg_map.get(&(previous_year, previous_month)) {
+                    let (mut local_total, mut currency_total) = (0.0, 0.0);
+                    for (account_id, &(amount, rate, is_liability)) in &agg.balances {
+                        let Some(&(previous_amount, previous_rate, _)) =
+                            previous.balances.get(account_id)
+                        else {
+                            continue;
+                        };
+                        let local = (amount - previous_amount) * previous_rate;
+                        let effect = amount * (rate - previous_rate);
+                        let sign = if is_liability { -1.0 } else { 1.0 };
+                        local_total += sign * local;
+                        currency_total += sign * effect;
+
+                        let currency = currencies.get(account_id);
+                        if let Some(currency) = currency.filter(|c| **c != home_currency) {
+                            fx_attribution.push(FxAttribution {
+                                account_id: account_id.clone(),
+                                currency: currency.clone(),
+                                local_change: local,
+                                currency_effect: effect,
+                            });
+                        }
+                    }
+                    fx_attribution.sort_by(|a, b| a.account_id.cmp(&b.account_id));
+                    local_change = Some(local_total);
+                    currency_effect = Some(currency_total);
+                }
+
+                let mut warnings = agg.warnings.clone();
+                warnings.sort_by(|a, b| a.currency.cmp(&b.currency));
+                let gross = agg.assets.abs() + agg.liabilities.abs();
+                let estimated_share = if gross > 0.0 {
+                    (agg.estimated / gross).min(1.0)
+                } else {
+                    0.0
+                };
+                let mut missing_account_ids: Vec<String> = year_accounts
+                    .get(&year)
+                    .map(|expected| expected.difference(&agg.account_ids).cloned().collect())
+                    .unwrap_or_default();
+                missing_account_ids.sort();
+                NetWorthDataPoint {
+                    year,
+                    month,
+                    total_assets: agg.assets,
+                    total_liabilities: agg.liabilities,
+                    net_worth: agg.assets - agg.liabilities,
+                    currency: home_currency.clone(),
+                    rate_quality: warnings
+                        .iter()
+                        .map(|w| w.quality)
+                        .max()
+                        .unwrap_or(RateQuality::Exact),
+                    warnings,
+                    estimated_share,
+                    missing_account_ids,
+                    local_change,
+                    currency_effect,
+                    fx_attribution,
+                }
             })
             .collect();
 
@@ -138,10 +360,255 @@ impl NetWorthService {
         Ok(result)
     }
 
-    pub async fn get_latest(pool: &SqlitePool) -> Result<Option<NetWorthDataPoint>, String> {
+    /**
+     * Per-month totals grouped by sub-category and by account, converted with the same rates
+     * as the history. Accepts the same filters as `query_history`.
+     */
+    pub async fn get_breakdown(
+        pool: &SqlitePool,
+        query: &NetWorthQuery,
+    ) -> Result<Vec<NetWorthBreakdown>, AppError> {
+        let (home_currency, converted) = Self::load_converted(pool, query).await?;
+
+        let mut month_map: HashMap<(i32, u32), Vec<ConvertedEntry>> = HashMap::new();
+        for item in converted {
+            month_map
+                .entry((item.year, item.month))
+                .or_default()
+                .push(item);
+        }
+
+        let mut result: Vec<NetWorthBreakdown> = month_map
+            .into_iter()
+            .filter(|((year, month), _)| Self::is_past_or_current(*year, *month))
+            .map(|((year, month), mut items)| {
+                items.sort_by(|a, b| {
+                    a.sort_order
+                        .cmp(&b.sort_order)
+                        .then_with(|| a.account_id.cmp(&b.account_id))
+                });
+
+                let mut warnings: Vec<RateWarning> = Vec::new();
+                let mut by_sub_category: Vec<SubCategoryTotal> = Vec::new();
+                let mut by_account: Vec<AccountTotal> = Vec::new();
+                for item in items {
+                    if let Some(warning) = &item.warning {
+                        if !warnings.contains(warning) {
+                            warnings.push(warning.clone());
+                        }
+                    }
+                    let Some(amount) = item.amount() else {
+                        continue;
+                    };
+
+                    match by_sub_category.iter_mut().find(|group| {
+                        group.account_type == item.account_type
+                            && group.sub_category == item.sub_category
+                    }) {
+                        Some(group) => group.total += amount,
+                        None => by_sub_category.push(SubCategoryTotal {
+                            account_type: item.account_type.clone(),
+                            sub_category: item.sub_category.clone(),
+                            total: amount,
+                        }),
+                    }
+                    by_account.push(AccountTotal {
+                        account_id: item.account_id,
+                        account_type: item.account_type,
+                        sub_category: item.sub_category,
+                        total: amount,
+                    });
+                }
+                by_sub_category.sort_by(|a, b| {
+                    a.account_type
+                        .cmp(&b.account_type)
+                        .then_with(|| a.sub_category.cmp(&b.sub_category))
+                });
+                warnings.sort_by(|a, b| a.currency.cmp(&b.currency));
+
+                NetWorthBreakdown {
+                    year,
+                    month,
+                    currency: home_currency.clone(),
+                    by_sub_category,
+                    by_account,
+                    rate_quality: warnings
+                        .iter()
+                        .map(|w| w.quality)
+                        .max()
+                        .unwrap_or(RateQuality::Exact),
+                    warnings,
+                }
+            })
+            .collect();
+
+        result.sort_by(|a, b| a.year.cmp(&b.year).then_with(|| a.month.cmp(&b.month)));
+        Ok(result)
+    }
+
+    // Loads the matching entries and converts each into the home currency
+    async fn load_converted(
+        pool: &SqlitePool,
+        query: &NetWorthQuery,
+    ) -> Result<(String, Vec<ConvertedEntry>), AppError> {
+        query.validate()?;
+
+        // 1. Get Home Currency
+        let settings = crate::services::user_settings::UserSettingsService::get_all(pool)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::missing("User settings"))?;
+        let home_currency = settings.home_currency;
+
+        // 2. Fetch the matching entries, plus the lookups needed to convert them
+        let entries = Self::query_entries(pool, query).await?;
+        let accounts = crate::services::account::AccountService::get_all(pool, true).await?;
+        let sheets = crate::services::balance_sheet::BalanceSheetService::get_all(pool).await?;
+        let converter = CurrencyConverter::load(pool).await?;
+
+        // 3. Build fast lookups
+        let account_map: HashMap<String, Account> =
+            accounts.into_iter().map(|a| (a.id.clone(), a)).collect();
+
+        let sheet_map: HashMap<String, BalanceSheet> =
+            sheets.into_iter().map(|s| (s.id.clone(), s)).collect();
+
+        // 4. Convert each entry
+        let mut converted = Vec::new();
+        for entry in entries {
+            // Entries of a trashed sheet or account drop out of the history until it's restored
+            let (Some(sheet), Some(account)) = (
+                sheet_map.get(&entry.balance_sheet_id),
+                account_map.get(&entry.account_id),
+            ) else {
+                continue;
+            };
+
+            let year = sheet.year;
+            let month = entry.month;
+
+            let (rate, warning) = if account.currency == home_currency {
+                (Some(1.0), None)
+            } else {
+                match converter.convert_rate(&account.currency, &home_currency, year, month) {
+                    Some(conversion) if conversion.is_exact(year, month) => {
+                        (Some(conversion.rate), None)
+                    }
+                    Some(conversion) => (
+                        Some(conversion.rate),
+                        Some(RateWarning {
+                            currency: account.currency.clone(),
+                            quality: RateQuality::Estimated,
+                            rate_year: Some(conversion.year),
+                            rate_month: Some(conversion.month),
+                        }),
+                    ),
+                    None => (
+                        None,
+                        Some(RateWarning {
+                            currency: account.currency.clone(),
+                            quality: RateQuality::Missing,
+                            rate_year: None,
+                            rate_month: None,
+                        }),
+                    ),
+                }
+            };
+
+            converted.push(ConvertedEntry {
+                year,
+                month,
+                account_id: account.id.clone(),
+                account_type: account.account_type.clone(),
+                sub_category: account.sub_category.clone(),
+                sort_order: account.sort_order,
+                is_archived: account.is_archived,
+                currency: account.currency.clone(),
+                status: entry.status,
+                local_amount: entry.amount,
+                rate,
+                warning,
+            });
+        }
+
+        Ok((home_currency, converted))
+    }
+
+    fn is_past_or_current(year: i32, month: u32) -> bool {
+        let now = chrono::Local::now();
+        match year.cmp(&now.year()) {
+            Ordering::Less => true,
+            Ordering::Equal => month <= now.month(),
+            Ordering::Greater => false,
+        }
+    }
+
+    async fn query_entries(
+        pool: &SqlitePool,
+        query: &NetWorthQuery,
+    ) -> Result<Vec<Entry>, AppError> {
+        // The id list goes in as a JSON array since SQLite can't bind a list
+        let account_ids = query
+            .account_ids
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| AppError::invalid(format!("Invalid account ids: {e}")))?;
+
+        sqlx::query_as::<_, Entry>(
+            "SELECT e.* FROM entries e
+             JOIN balance_sheets b ON b.id = e.balance_sheet_id
+             JOIN accounts a ON a.id = e.account_id
+             WHERE b.deleted_at IS NULL AND a.deleted_at IS NULL
+             AND ($1 IS NULL OR b.year * 12 + e.month >= $1)
+             AND ($2 IS NULL OR b.year * 12 + e.month <= $2)
+             AND ($3 IS NULL OR e.account_id IN (SELECT value FROM json_each($3)))
+             AND ($4 IS NULL OR a.account_type = $4)
+             AND ($5 IS NULL OR a.sub_category = $5)",
+        )
+        .bind(query.start_period())
+        .bind(query.end_period())
+        .bind(account_ids)
+        .bind(&query.account_type)
+        .bind(&query.sub_category)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    pub async fn get_latest(pool: &SqlitePool) -> Result<Option<NetWorthDataPoint>, AppError> {
         let mut history = Self::get_history(pool).await?;
         Ok(history.pop())
     }
+
+    pub async fn get_unconvertible_months(
+        pool: &SqlitePool,
+    ) -> Result<Vec<UnconvertibleMonth>, AppError> {
+        let history = Self::get_history(pool).await?;
+
+        Ok(history
+            .into_iter()
+            .filter(|point| point.rate_quality != RateQuality::Exact)
+            .map(|point| {
+                let currencies_with = |quality: RateQuality| {
+                    point
+                        .warnings
+                        .iter()
+                        .filter(|w| w.quality == quality)
+                        .map(|w| w.currency.clone())
+                        .collect()
+                };
+                UnconvertibleMonth {
+                    year: point.year,
+                    month: point.month,
+                    missing_currencies: currencies_with(RateQuality::Missing),
+                    estimated_currencies: currencies_with(RateQuality::Estimated),
+                    home_currency: point.currency,
+                }
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -219,17 +686,38 @@ mod tests {
 
         // 5. Add Entries (Month 1 - Jan)
         // Asset USD: 1000
-        EntryService::upsert(&pool, sheet.id.clone(), asset_usd.id.clone(), 1, 1000.0)
-            .await
-            .expect("entry 1");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_usd.id.clone(),
+            1,
+            1000.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry 1");
         // Asset EUR: 100
-        EntryService::upsert(&pool, sheet.id.clone(), asset_eur.id.clone(), 1, 100.0)
-            .await
-            .expect("entry 2");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_eur.id.clone(),
+            1,
+            100.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry 2");
         // Liab USD: 50
-        EntryService::upsert(&pool, sheet.id.clone(), liab_usd.id.clone(), 1, 50.0)
-            .await
-            .expect("entry 3");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            liab_usd.id.clone(),
+            1,
+            50.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry 3");
 
         // 6. Calculate
         let history = NetWorthService::get_history(&pool)
@@ -255,6 +743,633 @@ mod tests {
         assert!((point.net_worth - 1060.0).abs() < 0.001);
     }
 
+    #[tokio::test]
+    async fn test_net_worth_flags_estimated_and_missing_rates() {
+        let pool = setup_test_db().await;
+
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+
+        let asset_eur = AccountService::upsert(
+            &pool,
+            None,
+            "Euro Stash".into(),
+            "Asset".into(),
+            "EUR".into(),
+            None,
+        )
+        .await
+        .expect("asset eur");
+        let asset_jpy = AccountService::upsert(
+            &pool,
+            None,
+            "Yen Stash".into(),
+            "Asset".into(),
+            "JPY".into(),
+            None,
+        )
+        .await
+        .expect("asset jpy");
+
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet");
+
+        // Only January has a EUR rate, and JPY has none at all
+        CurrencyRateService::upsert(
+            &pool,
+            None,
+            "EUR".into(),
+            "USD".into(),
+            "manual".into(),
+            1.1,
+            1,
+            2025,
+        )
+        .await
+        .expect("rate");
+
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_eur.id.clone(),
+            1,
+            100.0,
+            Default::default(),
+        )
+        .await
+        .expect("eur jan");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_eur.id.clone(),
+            3,
+            200.0,
+            Default::default(),
+        )
+        .await
+        .expect("eur mar");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_jpy.id.clone(),
+            3,
+            10000.0,
+            Default::default(),
+        )
+        .await
+        .expect("jpy mar");
+
+        let history = NetWorthService::get_history(&pool)
+            .await
+            .expect("get history");
+        assert_eq!(history.len(), 2);
+
+        let jan = &history[0];
+        assert_eq!(jan.rate_quality, RateQuality::Exact);
+        assert!(jan.warnings.is_empty());
+        assert!((jan.net_worth - 110.0).abs() < 0.001);
+
+        // March: EUR converted with January's rate, JPY excluded rather than treated as 1:1
+        let mar = &history[1];
+        assert_eq!(mar.month, 3);
+        assert_eq!(mar.rate_quality, RateQuality::Missing);
+        assert!((mar.total_assets - 220.0).abs() < 0.001);
+        assert_eq!(
+            mar.warnings,
+            vec![
+                RateWarning {
+                    currency: "EUR".into(),
+                    quality: RateQuality::Estimated,
+                    rate_year: Some(2025),
+                    rate_month: Some(1),
+                },
+                RateWarning {
+                    currency: "JPY".into(),
+                    quality: RateQuality::Missing,
+                    rate_year: None,
+                    rate_month: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unconvertible_months_after_home_currency_change() {
+        let pool = setup_test_db().await;
+
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+
+        let asset_eur = AccountService::upsert(
+            &pool,
+            None,
+            "Euro Stash".into(),
+            "Asset".into(),
+            "EUR".into(),
+            None,
+        )
+        .await
+        .expect("asset eur");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet");
+        CurrencyRateService::upsert(
+            &pool,
+            None,
+            "EUR".into(),
+            "USD".into(),
+            "manual".into(),
+            1.1,
+            1,
+            2025,
+        )
+        .await
+        .expect("rate");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_eur.id.clone(),
+            1,
+            100.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry");
+
+        assert!(NetWorthService::get_unconvertible_months(&pool)
+            .await
+            .expect("gaps in USD")
+            .is_empty());
+
+        // Nothing links EUR to GBP yet
+        UserSettingsService::upsert(&pool, "Test User".into(), "GBP".into(), "system".into())
+            .await
+            .expect("change home currency");
+
+        let gaps = NetWorthService::get_unconvertible_months(&pool)
+            .await
+            .expect("gaps in GBP");
+        assert_eq!(gaps.len(), 1);
+        assert_eq!((gaps[0].year, gaps[0].month), (2025, 1));
+        assert_eq!(gaps[0].home_currency, "GBP");
+        assert_eq!(gaps[0].missing_currencies, vec!["EUR".to_string()]);
+        assert!(gaps[0].estimated_currencies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_net_worth_reports_estimated_share() {
+        use crate::services::entry::{
+            EntryProvenance, ENTRY_STATUS_CARRIED_FORWARD, ENTRY_STATUS_IMPORTED,
+        };
+
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("cash");
+        let house = AccountService::upsert(
+            &pool,
+            None,
+            "House".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("house");
+        let loan = AccountService::upsert(
+            &pool,
+            None,
+            "Loan".into(),
+            "Liability".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("loan");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            cash.id,
+            1,
+            1000.0,
+            Default::default(),
+        )
+        .await
+        .expect("cash");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            house.id,
+            1,
+            3000.0,
+            EntryProvenance {
+                note: Some("Agent's rough valuation".into()),
+                ..EntryProvenance::new(ENTRY_STATUS_CARRIED_FORWARD, None)
+            },
+        )
+        .await
+        .expect("house");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            loan.id,
+            1,
+            1000.0,
+            EntryProvenance::new(ENTRY_STATUS_IMPORTED, Some("csv_import")),
+        )
+        .await
+        .expect("loan");
+
+        let history = NetWorthService::get_history(&pool).await.expect("history");
+        assert_eq!(history.len(), 1);
+        // 3000 of 5000 in assets plus liabilities is carried forward
+        assert!((history[0].estimated_share - 0.6).abs() < 1e-9);
+
+        let carried = EntryService::get_by_balance_sheet(
+            &pool,
+            sheet.id,
+            Some(ENTRY_STATUS_CARRIED_FORWARD.into()),
+        )
+        .await
+        .expect("carried");
+        assert_eq!(carried.len(), 1);
+        assert_eq!(carried[0].note.as_deref(), Some("Agent's rough valuation"));
+    }
+
+    #[tokio::test]
+    async fn test_net_worth_separates_missing_from_zero() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("cash");
+        let fund = AccountService::upsert(
+            &pool,
+            None,
+            "Fund".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("fund");
+
+        for (account_id, month, amount) in [
+            (&cash.id, 1, 100.0),
+            (&fund.id, 1, 500.0),
+            (&cash.id, 2, 150.0),
+            (&fund.id, 2, 0.0),
+            (&cash.id, 3, 200.0),
+        ] {
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account_id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("entry");
+        }
+
+        let history = NetWorthService::get_history(&pool).await.expect("history");
+        assert_eq!(history.len(), 3);
+        // A zero balance is real data
+        assert!(history[1].missing_account_ids.is_empty());
+        // No entry at all is flagged as missing
+        assert_eq!(history[2].missing_account_ids, vec![fund.id]);
+        assert_eq!(history[2].net_worth, 200.0);
+    }
+
+    #[tokio::test]
+    async fn test_query_history_filters() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("cash");
+        let shares = AccountService::upsert(
+            &pool,
+            None,
+            "Shares".into(),
+            "Asset".into(),
+            "USD".into(),
+            Some("investments".into()),
+        )
+        .await
+        .expect("shares");
+        let loan = AccountService::upsert(
+            &pool,
+            None,
+            "Loan".into(),
+            "Liability".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("loan");
+
+        for year in [2021, 2022] {
+            let sheet = BalanceSheetService::upsert(&pool, None, year)
+                .await
+                .expect("sheet");
+            for month in [6, 12] {
+                for (account_id, amount) in
+                    [(&cash.id, 100.0), (&shares.id, 1000.0), (&loan.id, 50.0)]
+                {
+                    EntryService::upsert(
+                        &pool,
+                        sheet.id.clone(),
+                        account_id.clone(),
+                        month,
+                        amount,
+                        Default::default(),
+                    )
+                    .await
+                    .expect("entry");
+                }
+            }
+        }
+
+        let investments = NetWorthService::query_history(
+            &pool,
+            &NetWorthQuery {
+                from_year: Some(2021),
+                from_month: Some(7),
+                sub_category: Some("investments".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("investments");
+        let periods: Vec<_> = investments.iter().map(|p| (p.year, p.month)).collect();
+        assert_eq!(periods, vec![(2021, 12), (2022, 6), (2022, 12)]);
+        assert!(investments.iter().all(|p| p.net_worth == 1000.0));
+
+        let liabilities = NetWorthService::query_history(
+            &pool,
+            &NetWorthQuery {
+                to_year: Some(2021),
+                account_type: Some("Liability".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("liabilities");
+        assert_eq!(liabilities.len(), 2);
+        assert!(liabilities.iter().all(|p| p.net_worth == -50.0));
+
+        let picked = NetWorthService::query_history(
+            &pool,
+            &NetWorthQuery {
+                account_ids: Some(vec![cash.id.clone(), loan.id.clone()]),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("picked accounts");
+        assert_eq!(picked.len(), 4);
+        assert!(picked.iter().all(|p| p.net_worth == 50.0));
+
+        let invalid = NetWorthService::query_history(
+            &pool,
+            &NetWorthQuery {
+                to_month: Some(13),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(invalid, Err(AppError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_fx_attribution() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("cash");
+        let euros = AccountService::upsert(
+            &pool,
+            None,
+            "Euro Stash".into(),
+            "Asset".into(),
+            "EUR".into(),
+            None,
+        )
+        .await
+        .expect("euros");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+        for (month, rate) in [(1, 1.1), (2, 1.2)] {
+            CurrencyRateService::upsert(
+                &pool,
+                None,
+                "EUR".into(),
+                "USD".into(),
+                "manual".into(),
+                rate,
+                month,
+                2024,
+            )
+            .await
+            .expect("rate");
+        }
+        for (account_id, month, amount) in [
+            (&cash.id, 1, 500.0),
+            (&cash.id, 2, 450.0),
+            (&euros.id, 1, 100.0),
+            (&euros.id, 2, 110.0),
+        ] {
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account_id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("entry");
+        }
+
+        let history = NetWorthService::get_history(&pool).await.expect("history");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].local_change, None);
+        assert!(history[0].fx_attribution.is_empty());
+
+        let february = &history[1];
+        let euro = &february.fx_attribution[0];
+        assert_eq!(february.fx_attribution.len(), 1);
+        assert_eq!(euro.account_id, euros.id);
+        assert_eq!(euro.currency, "EUR");
+        // 10 more euros at January's rate, and the 110 held gaining 0.1 each
+        assert!((euro.local_change - 11.0).abs() < 1e-9);
+        assert!((euro.currency_effect - 11.0).abs() < 1e-9);
+
+        // The split adds up to the change in net worth
+        let local = february.local_change.expect("local change");
+        let effect = february.currency_effect.expect("currency effect");
+        assert!((local - (-50.0 + 11.0)).abs() < 1e-9);
+        assert!((local + effect - (february.net_worth - history[0].net_worth)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_breakdown_by_sub_category_and_account() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            Some("cash".into()),
+        )
+        .await
+        .expect("cash");
+        let shares = AccountService::upsert(
+            &pool,
+            None,
+            "Shares".into(),
+            "Asset".into(),
+            "EUR".into(),
+            Some("investments".into()),
+        )
+        .await
+        .expect("shares");
+        let fund = AccountService::upsert(
+            &pool,
+            None,
+            "Fund".into(),
+            "Asset".into(),
+            "USD".into(),
+            Some("investments".into()),
+        )
+        .await
+        .expect("fund");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+        CurrencyRateService::upsert(
+            &pool,
+            None,
+            "EUR".into(),
+            "USD".into(),
+            "manual".into(),
+            2.0,
+            1,
+            2024,
+        )
+        .await
+        .expect("rate");
+
+        for (account_id, month, amount) in [
+            (&cash.id, 1, 100.0),
+            (&shares.id, 1, 50.0),
+            (&fund.id, 1, 200.0),
+            (&shares.id, 2, 60.0),
+        ] {
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account_id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("entry");
+        }
+
+        let breakdown = NetWorthService::get_breakdown(&pool, &NetWorthQuery::default())
+            .await
+            .expect("breakdown");
+        assert_eq!(breakdown.len(), 2);
+
+        let january = &breakdown[0];
+        assert_eq!(january.rate_quality, RateQuality::Exact);
+        let groups: Vec<_> = january
+            .by_sub_category
+            .iter()
+            .map(|g| (g.sub_category.as_deref(), g.total))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![(Some("cash"), 100.0), (Some("investments"), 300.0)]
+        );
+        let accounts: Vec<_> = january
+            .by_account
+            .iter()
+            .map(|a| (a.account_id.as_str(), a.total))
+            .collect();
+        assert_eq!(
+            accounts,
+            vec![
+                (cash.id.as_str(), 100.0),
+                (shares.id.as_str(), 100.0),
+                (fund.id.as_str(), 200.0)
+            ]
+        );
+
+        // February reuses January's rate, same as the history
+        let february = &breakdown[1];
+        assert_eq!(february.rate_quality, RateQuality::Estimated);
+        assert_eq!(february.by_account.len(), 1);
+        assert_eq!(february.by_account[0].total, 120.0);
+    }
+
     #[tokio::test]
     async fn test_net_worth_future_exclusion() {
         let pool = setup_test_db().await;
@@ -283,9 +1398,16 @@ mod tests {
             .expect("sheet");
 
         // 4. Add Entry for Future
-        EntryService::upsert(&pool, sheet.id.clone(), asset_usd.id.clone(), 1, 5000.0)
-            .await
-            .expect("entry future");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_usd.id.clone(),
+            1,
+            5000.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry future");
 
         // 5. Calculate
         let history = NetWorthService::get_history(&pool)
@@ -319,12 +1441,26 @@ mod tests {
             .await
             .expect("sheet");
 
-        EntryService::upsert(&pool, sheet.id.clone(), asset_usd.id.clone(), 1, 1000.0)
-            .await
-            .expect("entry jan");
-        EntryService::upsert(&pool, sheet.id.clone(), asset_usd.id.clone(), 2, 1500.0)
-            .await
-            .expect("entry feb");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_usd.id.clone(),
+            1,
+            1000.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry jan");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            asset_usd.id.clone(),
+            2,
+            1500.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry feb");
 
         let latest = NetWorthService::get_latest(&pool).await.expect("latest");
 
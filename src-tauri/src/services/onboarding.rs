@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::models::OnboardingStep;
 use crate::services::account::AccountService;
 use crate::services::balance_sheet::BalanceSheetService;
@@ -11,7 +12,7 @@ pub const STEP_CREATE_FIRST_BALANCE_SHEET: &str = "CREATE_FIRST_BALANCE_SHEET";
 pub struct OnboardingService;
 
 impl OnboardingService {
-    pub async fn get_status(pool: &SqlitePool) -> Result<Vec<OnboardingStep>, String> {
+    pub async fn get_status(pool: &SqlitePool) -> Result<Vec<OnboardingStep>, AppError> {
         // Ensure steps exist
         Self::ensure_steps_exist(pool).await?;
 
@@ -21,19 +22,18 @@ impl OnboardingService {
         sqlx::query_as::<_, OnboardingStep>("SELECT * FROM onboarding_steps")
             .fetch_all(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
     }
 
-    pub async fn complete_step(pool: &SqlitePool, step_key: String) -> Result<(), String> {
+    pub async fn complete_step(pool: &SqlitePool, step_key: String) -> Result<(), AppError> {
         sqlx::query("UPDATE onboarding_steps SET is_completed = 1, updated_at = CURRENT_TIMESTAMP WHERE step_key = ? AND is_completed = 0")
             .bind(step_key)
             .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
         Ok(())
     }
 
-    async fn ensure_steps_exist(pool: &SqlitePool) -> Result<(), String> {
+    async fn ensure_steps_exist(pool: &SqlitePool) -> Result<(), AppError> {
         let steps = vec![
             STEP_CONFIGURE_SETTINGS,
             STEP_CREATE_FIRST_ACCOUNT,
@@ -46,14 +46,13 @@ impl OnboardingService {
             )
             .bind(step)
             .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
         }
 
         Ok(())
     }
 
-    pub async fn auto_complete_steps(pool: &SqlitePool) -> Result<(), String> {
+    pub async fn auto_complete_steps(pool: &SqlitePool) -> Result<(), AppError> {
         // Step 1: Settings
         let settings = UserSettingsService::get_all(pool).await?;
         if !settings.is_empty() {
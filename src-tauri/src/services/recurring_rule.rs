@@ -0,0 +1,255 @@
+use crate::error::AppError;
+use crate::models::RecurringRule;
+use crate::services::account::AccountService;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+pub const RULE_TYPE_FIXED_AMOUNT: &str = "fixed_amount";
+pub const RULE_TYPE_FIXED_DELTA: &str = "fixed_delta";
+pub const RULE_TYPE_PERCENTAGE_GROWTH: &str = "percentage_growth";
+pub const RULE_TYPE_AMORTIZATION: &str = "amortization";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringRuleInput {
+    pub account_id: String,
+    pub rule_type: String,
+    pub amount: f64,
+    pub rate: f64,
+    pub is_active: bool,
+}
+
+pub struct RecurringRuleService;
+
+impl RecurringRuleService {
+    // LIST (rules of trashed accounts stay hidden until the account is restored)
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<RecurringRule>, AppError> {
+        sqlx::query_as::<_, RecurringRule>(
+            "SELECT r.* FROM recurring_rules r JOIN accounts a ON a.id = r.account_id WHERE a.deleted_at IS NULL",
+        )
+            .fetch_all(pool)
+            .await
+            .map_err(AppError::from)
+    }
+
+    // UPSERT (one rule per account)
+    pub async fn upsert(
+        pool: &SqlitePool,
+        id: Option<String>,
+        input: RecurringRuleInput,
+    ) -> Result<RecurringRule, AppError> {
+        Self::validate(&input)?;
+        if AccountService::get_by_id(pool, input.account_id.clone())
+            .await?
+            .is_none()
+        {
+            return Err(AppError::not_found("Account", input.account_id));
+        }
+
+        let now = chrono::Utc::now();
+        let account_conflict = |e: sqlx::Error| match &e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                AppError::conflict("RecurringRule", "This account already has a recurring rule")
+            }
+            _ => AppError::from(e),
+        };
+
+        if let Some(uid) = id {
+            return sqlx::query_as::<_, RecurringRule>(
+                "UPDATE recurring_rules SET account_id = ?, rule_type = ?, amount = ?, rate = ?, is_active = ?, updated_at = ? WHERE id = ? RETURNING *",
+            )
+            .bind(&input.account_id)
+            .bind(&input.rule_type)
+            .bind(input.amount)
+            .bind(input.rate)
+            .bind(input.is_active)
+            .bind(now)
+            .bind(&uid)
+            .fetch_optional(pool)
+            .await
+            .map_err(account_conflict)?
+            .ok_or_else(|| AppError::not_found("RecurringRule", uid));
+        }
+
+        sqlx::query_as::<_, RecurringRule>(
+            "INSERT INTO recurring_rules (id, account_id, rule_type, amount, rate, is_active, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&input.account_id)
+        .bind(&input.rule_type)
+        .bind(input.amount)
+        .bind(input.rate)
+        .bind(input.is_active)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(account_conflict)
+    }
+
+    // DELETE
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM recurring_rules WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    fn validate(input: &RecurringRuleInput) -> Result<(), AppError> {
+        match input.rule_type.as_str() {
+            RULE_TYPE_FIXED_AMOUNT
+            | RULE_TYPE_FIXED_DELTA
+            | RULE_TYPE_PERCENTAGE_GROWTH
+            | RULE_TYPE_AMORTIZATION => {}
+            other => {
+                return Err(AppError::validation(
+                    "ruleType",
+                    format!("Unknown recurring rule type: {other}"),
+                ))
+            }
+        }
+        if !input.amount.is_finite() {
+            return Err(AppError::validation(
+                "amount",
+                format!("Invalid amount: {}", input.amount),
+            ));
+        }
+        if !input.rate.is_finite() || input.rate <= -1.0 {
+            return Err(AppError::validation(
+                "rate",
+                format!("Invalid rate: {}", input.rate),
+            ));
+        }
+        Ok(())
+    }
+
+    /**
+     * The balance a rule produces for the month after `previous`, rounded to cents.
+     * Every rule but `fixed_amount` needs a previous balance to build on.
+     * Amortization charges a month of interest on the balance, then subtracts the payment,
+     * and never goes below zero.
+     */
+    pub fn next_amount(rule: &RecurringRule, previous: Option<f64>) -> Option<f64> {
+        let amount = match rule.rule_type.as_str() {
+            RULE_TYPE_FIXED_AMOUNT => rule.amount,
+            RULE_TYPE_FIXED_DELTA => previous? + rule.amount,
+            RULE_TYPE_PERCENTAGE_GROWTH => previous? * (1.0 + rule.rate).powf(1.0 / 12.0),
+            RULE_TYPE_AMORTIZATION => (previous? * (1.0 + rule.rate / 12.0) - rule.amount).max(0.0),
+            _ => return None,
+        };
+        Some((amount * 100.0).round() / 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    fn rule(rule_type: &str, amount: f64, rate: f64) -> RecurringRule {
+        RecurringRule {
+            id: "rule".into(),
+            account_id: "account".into(),
+            rule_type: rule_type.into(),
+            amount,
+            rate,
+            is_active: true,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn next_amount_applies_each_rule_type() {
+        let fixed = rule(RULE_TYPE_FIXED_AMOUNT, 250.0, 0.0);
+        assert_eq!(RecurringRuleService::next_amount(&fixed, None), Some(250.0));
+
+        let delta = rule(RULE_TYPE_FIXED_DELTA, 100.0, 0.0);
+        assert_eq!(RecurringRuleService::next_amount(&delta, None), None);
+        assert_eq!(
+            RecurringRuleService::next_amount(&delta, Some(1000.0)),
+            Some(1100.0)
+        );
+
+        // 12.68% a year is 1% a month
+        let growth = rule(RULE_TYPE_PERCENTAGE_GROWTH, 0.0, 1.01_f64.powi(12) - 1.0);
+        assert_eq!(
+            RecurringRuleService::next_amount(&growth, Some(1000.0)),
+            Some(1010.0)
+        );
+
+        // 300k at 6% a year accrues 1500 interest before a 2000 payment
+        let mortgage = rule(RULE_TYPE_AMORTIZATION, 2000.0, 0.06);
+        assert_eq!(
+            RecurringRuleService::next_amount(&mortgage, Some(300_000.0)),
+            Some(299_500.0)
+        );
+        assert_eq!(
+            RecurringRuleService::next_amount(&mortgage, Some(500.0)),
+            Some(0.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recurring_rule_crud() {
+        let pool = setup_test_db().await;
+        let account = AccountService::upsert(
+            &pool,
+            None,
+            "Mortgage".into(),
+            "Liability".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("account");
+
+        let input = RecurringRuleInput {
+            account_id: account.id.clone(),
+            rule_type: RULE_TYPE_AMORTIZATION.into(),
+            amount: 2000.0,
+            rate: 0.06,
+            is_active: true,
+        };
+        let created = RecurringRuleService::upsert(&pool, None, input.clone())
+            .await
+            .expect("create");
+
+        let duplicate = RecurringRuleService::upsert(&pool, None, input.clone()).await;
+        assert!(matches!(duplicate, Err(AppError::Conflict { .. })));
+
+        let unknown = RecurringRuleService::upsert(
+            &pool,
+            None,
+            RecurringRuleInput {
+                rule_type: "compound".into(),
+                ..input.clone()
+            },
+        )
+        .await;
+        assert!(matches!(unknown, Err(AppError::Validation { .. })));
+
+        let updated = RecurringRuleService::upsert(
+            &pool,
+            Some(created.id.clone()),
+            RecurringRuleInput {
+                amount: 2500.0,
+                ..input
+            },
+        )
+        .await
+        .expect("update");
+        assert_eq!(updated.id, created.id);
+        assert_eq!(updated.amount, 2500.0);
+
+        RecurringRuleService::delete(&pool, created.id)
+            .await
+            .expect("delete");
+        assert!(RecurringRuleService::get_all(&pool)
+            .await
+            .expect("list")
+            .is_empty());
+    }
+}
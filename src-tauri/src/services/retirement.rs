@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use chrono::{Datelike, Duration, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 
@@ -45,12 +46,15 @@ impl RetirementService {
         expected_monthly_expenses * (1.0 + inflation_rate).powf(years)
     }
 
-    pub fn annual_return_rate(scenario: &str) -> Result<f64, String> {
+    pub fn annual_return_rate(scenario: &str) -> Result<f64, AppError> {
         match scenario {
             RETURN_SCENARIO_CONSERVATIVE => Ok(RETURN_RATE_CONSERVATIVE),
             RETURN_SCENARIO_MODERATE => Ok(RETURN_RATE_MODERATE),
             RETURN_SCENARIO_AGGRESSIVE => Ok(RETURN_RATE_AGGRESSIVE),
-            _ => Err(format!("Unknown return scenario: {scenario}")),
+            _ => Err(AppError::validation(
+                "returnScenario",
+                format!("Unknown return scenario: {scenario}"),
+            )),
         }
     }
 
@@ -219,7 +223,7 @@ impl RetirementService {
         return_scenario: &str,
         target_retirement_year: Option<i32>,
         inflation_rate: f64,
-    ) -> Result<RetirementProjection, String> {
+    ) -> Result<RetirementProjection, AppError> {
         let annual_return_rate = Self::annual_return_rate(return_scenario)?;
         let today = Local::now().date_naive();
         let target_retirement_date = match target_retirement_year {
@@ -255,7 +259,7 @@ impl RetirementService {
                     inflation_rate,
                 )
                 .ok_or_else(|| {
-                    "Retirement goal is not achievable with current inputs".to_string()
+                    AppError::invalid("Retirement goal is not achievable with current inputs")
                 })?;
 
                 let date = if years <= 0.0 {
@@ -1,6 +1,8 @@
+use crate::error::AppError;
 use crate::models::RetirementPlan;
+use crate::services::audit::{AuditService, AUDIT_ENTITY_RETIREMENT_PLAN};
 use chrono::NaiveDate;
-use sqlx::{Executor, Sqlite, SqlitePool};
+use sqlx::{Sqlite, SqlitePool};
 use uuid::Uuid;
 
 pub struct RetirementPlanService;
@@ -16,9 +18,10 @@ impl RetirementPlanService {
         expected_monthly_expenses: f64,
         return_scenario: String,
         inflation_rate: f64,
-    ) -> Result<RetirementPlan, String> {
-        Self::create_with_executor(
-            pool,
+    ) -> Result<RetirementPlan, AppError> {
+        let mut tx = pool.begin().await?;
+        let plan = Self::create_in_tx(
+            &mut tx,
             name,
             target_retirement_year,
             starting_net_worth,
@@ -27,11 +30,13 @@ impl RetirementPlanService {
             return_scenario,
             inflation_rate,
         )
-        .await
+        .await?;
+        tx.commit().await?;
+        Ok(plan)
     }
 
-    pub async fn create_with_executor<'e, E>(
-        executor: E,
+    pub async fn create_in_tx(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
         name: String,
         target_retirement_year: Option<i32>,
         starting_net_worth: f64,
@@ -39,10 +44,7 @@ impl RetirementPlanService {
         expected_monthly_expenses: f64,
         return_scenario: String,
         inflation_rate: f64,
-    ) -> Result<RetirementPlan, String>
-    where
-        E: Executor<'e, Database = Sqlite>,
-    {
+    ) -> Result<RetirementPlan, AppError> {
         let new_id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now();
         let target_retirement_date = match target_retirement_year {
@@ -50,7 +52,7 @@ impl RetirementPlanService {
             None => None,
         };
 
-        sqlx::query_as::<_, RetirementPlan>(
+        let plan = sqlx::query_as::<_, RetirementPlan>(
             "INSERT INTO retirement_plans (id, name, target_retirement_date, starting_net_worth, monthly_contribution, expected_monthly_expenses, return_scenario, inflation_rate, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING *",
         )
         .bind(new_id)
@@ -63,29 +65,49 @@ impl RetirementPlanService {
         .bind(inflation_rate)
         .bind(now)
         .bind(now)
-        .fetch_one(executor)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        AuditService::record(
+            tx,
+            AUDIT_ENTITY_RETIREMENT_PLAN,
+            &plan.id,
+            None,
+            Some(&plan),
+        )
+        .await?;
+        Ok(plan)
+    }
+
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<RetirementPlan>, AppError> {
+        sqlx::query_as::<_, RetirementPlan>(
+            "SELECT * FROM retirement_plans WHERE deleted_at IS NULL ORDER BY updated_at DESC",
+        )
+        .fetch_all(pool)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
     }
 
-    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<RetirementPlan>, String> {
+    pub async fn get_trashed(pool: &SqlitePool) -> Result<Vec<RetirementPlan>, AppError> {
         sqlx::query_as::<_, RetirementPlan>(
-            "SELECT * FROM retirement_plans ORDER BY updated_at DESC",
+            "SELECT * FROM retirement_plans WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
         )
         .fetch_all(pool)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
     }
 
     pub async fn get_by_id(
         pool: &SqlitePool,
         id: String,
-    ) -> Result<Option<RetirementPlan>, String> {
-        sqlx::query_as::<_, RetirementPlan>("SELECT * FROM retirement_plans WHERE id = ?")
-            .bind(id)
-            .fetch_optional(pool)
-            .await
-            .map_err(|e| e.to_string())
+    ) -> Result<Option<RetirementPlan>, AppError> {
+        sqlx::query_as::<_, RetirementPlan>(
+            "SELECT * FROM retirement_plans WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::from)
     }
 
     pub async fn update(
@@ -98,10 +120,14 @@ impl RetirementPlanService {
         expected_monthly_expenses: f64,
         return_scenario: String,
         inflation_rate: f64,
-    ) -> Result<RetirementPlan, String> {
+    ) -> Result<RetirementPlan, AppError> {
         let now = chrono::Utc::now();
+        let existing = Self::get_by_id(pool, id.clone())
+            .await?
+            .ok_or_else(|| AppError::not_found("RetirementPlan", id.clone()))?;
 
-        sqlx::query_as::<_, RetirementPlan>(
+        let mut tx = pool.begin().await?;
+        let plan = sqlx::query_as::<_, RetirementPlan>(
             "UPDATE retirement_plans SET name = ?, target_retirement_date = ?, starting_net_worth = ?, monthly_contribution = ?, expected_monthly_expenses = ?, return_scenario = ?, inflation_rate = ?, updated_at = ? WHERE id = ? RETURNING *",
         )
         .bind(name)
@@ -113,17 +139,91 @@ impl RetirementPlanService {
         .bind(inflation_rate)
         .bind(now)
         .bind(id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| e.to_string())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_RETIREMENT_PLAN,
+            &plan.id,
+            Some(&existing),
+            Some(&plan),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(plan)
     }
 
-    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), String> {
-        sqlx::query("DELETE FROM retirement_plans WHERE id = ?")
-            .bind(id)
-            .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+    // Moves the plan to the trash
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let existing = Self::get_by_id(pool, id.clone()).await?;
+
+        let mut tx = pool.begin().await?;
+        let trashed = sqlx::query_as::<_, RetirementPlan>(
+            "UPDATE retirement_plans SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL RETURNING *",
+        )
+        .bind(chrono::Utc::now())
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_RETIREMENT_PLAN,
+            &id,
+            existing.as_ref(),
+            trashed.as_ref(),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn restore(pool: &SqlitePool, id: String) -> Result<RetirementPlan, AppError> {
+        let mut tx = pool.begin().await?;
+        let trashed = sqlx::query_as::<_, RetirementPlan>(
+            "SELECT * FROM retirement_plans WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::not_found("RetirementPlan", id.clone()))?;
+        let restored = sqlx::query_as::<_, RetirementPlan>(
+            "UPDATE retirement_plans SET deleted_at = NULL WHERE id = ? RETURNING *",
+        )
+        .bind(&id)
+        .fetch_one(&mut *tx)
+        .await?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_RETIREMENT_PLAN,
+            &id,
+            Some(&trashed),
+            Some(&restored),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(restored)
+    }
+
+    // Permanently deletes a trashed plan along with its projections
+    pub async fn purge(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let existing = sqlx::query_as::<_, RetirementPlan>(
+            "DELETE FROM retirement_plans WHERE id = ? AND deleted_at IS NOT NULL RETURNING *",
+        )
+        .bind(&id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::not_found("RetirementPlan", id.clone()))?;
+        AuditService::record(
+            &mut tx,
+            AUDIT_ENTITY_RETIREMENT_PLAN,
+            &id,
+            Some(&existing),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
         Ok(())
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::models::RetirementPlanProjection;
 use crate::services::retirement::ProjectionDataPoint;
 use sqlx::{Sqlite, SqlitePool};
@@ -7,12 +8,11 @@ pub struct RetirementPlanProjectionService;
 
 impl RetirementPlanProjectionService {
     #[allow(dead_code)]
-    pub async fn delete_by_plan_id(pool: &SqlitePool, plan_id: &str) -> Result<(), String> {
+    pub async fn delete_by_plan_id(pool: &SqlitePool, plan_id: &str) -> Result<(), AppError> {
         sqlx::query("DELETE FROM retirement_plan_projections WHERE plan_id = ?")
             .bind(plan_id)
             .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
         Ok(())
     }
 
@@ -20,12 +20,12 @@ impl RetirementPlanProjectionService {
         pool: &SqlitePool,
         plan_id: &str,
         data_points: Vec<ProjectionDataPoint>,
-    ) -> Result<Vec<RetirementPlanProjection>, String> {
-        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    ) -> Result<Vec<RetirementPlanProjection>, AppError> {
+        let mut tx = pool.begin().await?;
 
         let projections = Self::save_projections_in_tx(&mut tx, plan_id, data_points).await?;
 
-        tx.commit().await.map_err(|e| e.to_string())?;
+        tx.commit().await?;
 
         Ok(projections)
     }
@@ -34,12 +34,11 @@ impl RetirementPlanProjectionService {
         tx: &mut sqlx::Transaction<'_, Sqlite>,
         plan_id: &str,
         data_points: Vec<ProjectionDataPoint>,
-    ) -> Result<Vec<RetirementPlanProjection>, String> {
+    ) -> Result<Vec<RetirementPlanProjection>, AppError> {
         sqlx::query("DELETE FROM retirement_plan_projections WHERE plan_id = ?")
             .bind(plan_id)
             .execute(&mut **tx)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         let now = chrono::Utc::now();
         let mut projections = Vec::with_capacity(data_points.len());
@@ -56,8 +55,7 @@ impl RetirementPlanProjectionService {
             .bind(point.projected_net_worth)
             .bind(now)
             .fetch_one(&mut **tx)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
             projections.push(projection);
         }
@@ -68,14 +66,14 @@ impl RetirementPlanProjectionService {
     pub async fn get_by_plan_id(
         pool: &SqlitePool,
         plan_id: &str,
-    ) -> Result<Vec<RetirementPlanProjection>, String> {
+    ) -> Result<Vec<RetirementPlanProjection>, AppError> {
         sqlx::query_as::<_, RetirementPlanProjection>(
             "SELECT * FROM retirement_plan_projections WHERE plan_id = ? ORDER BY year ASC, month ASC",
         )
         .bind(plan_id)
         .fetch_all(pool)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
     }
 }
 
@@ -217,9 +215,17 @@ mod tests {
             .await
             .expect("save");
 
+        // Projections survive the trash and only go when the plan is purged
         RetirementPlanService::delete(&pool, plan.id.clone())
             .await
             .expect("delete plan");
+        let trashed = RetirementPlanProjectionService::get_by_plan_id(&pool, &plan.id)
+            .await
+            .expect("get projections");
+        assert_eq!(trashed.len(), 1);
+        RetirementPlanService::purge(&pool, plan.id.clone())
+            .await
+            .expect("purge plan");
 
         let fetched = RetirementPlanProjectionService::get_by_plan_id(&pool, &plan.id)
             .await
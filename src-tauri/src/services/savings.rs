@@ -0,0 +1,607 @@
+use crate::error::AppError;
+use crate::models::{Account, Contribution, MonthlyIncome};
+use crate::services::account::AccountService;
+use crate::services::currency_rates::conversion::CurrencyConverter;
+use crate::services::net_worth::{NetWorthQuery, NetWorthService};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+/**
+ * Splits a month's change in net worth into what was saved and what the balances earned.
+ * Contributions come from the amounts entered for each account, or from the account's
+ * transactions for months without one; both are converted into the home currency.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavingsMonth {
+    pub year: i32,
+    pub month: u32,
+    pub currency: String,
+    // Against the previous calendar month; None when that month has no data
+    pub net_worth_change: Option<f64>,
+    pub contributions: f64,
+    // Part of `contributions` inferred from transactions
+    pub inferred_contributions: f64,
+    // net_worth_change - contributions
+    pub returns: Option<f64>,
+    pub income: Option<f64>,
+    // Contributions as a share (0-100) of income; None without a positive income
+    pub savings_rate: Option<f64>,
+    // Account currencies that couldn't be converted for this month
+    pub missing_currencies: Vec<String>,
+}
+
+pub struct SavingsService;
+
+impl SavingsService {
+    // LIST contributions for a year
+    pub async fn get_contributions(
+        pool: &SqlitePool,
+        year: i32,
+    ) -> Result<Vec<Contribution>, AppError> {
+        sqlx::query_as::<_, Contribution>(
+            "SELECT * FROM contributions WHERE year = ? ORDER BY month ASC",
+        )
+        .bind(year)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    /**
+     * UPSERT (business key: account + year + month)
+     * Contributions are taken as new money, e.g. salary paid into savings. Moving money between
+     * tracked accounts isn't one; it's a transfer, and its two legs cancel out in the report.
+     */
+    pub async fn set_contribution(
+        pool: &SqlitePool,
+        account_id: String,
+        year: i32,
+        month: u32,
+        amount: f64,
+    ) -> Result<Contribution, AppError> {
+        Self::validate_month(month)?;
+        if !amount.is_finite() {
+            return Err(AppError::validation(
+                "amount",
+                format!("Invalid contribution amount: {amount}"),
+            ));
+        }
+        if AccountService::get_by_id(pool, account_id.clone())
+            .await?
+            .is_none()
+        {
+            return Err(AppError::not_found("Account", account_id));
+        }
+
+        sqlx::query_as::<_, Contribution>(
+            "INSERT INTO contributions (id, account_id, year, month, amount, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (account_id, year, month) DO UPDATE SET
+                amount = excluded.amount,
+                updated_at = excluded.updated_at
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(account_id)
+        .bind(year)
+        .bind(month)
+        .bind(amount)
+        .bind(chrono::Utc::now())
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // DELETE contribution; the month falls back to the account's transactions
+    pub async fn delete_contribution(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM contributions WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // LIST income for a year
+    pub async fn get_income(pool: &SqlitePool, year: i32) -> Result<Vec<MonthlyIncome>, AppError> {
+        sqlx::query_as::<_, MonthlyIncome>(
+            "SELECT * FROM monthly_income WHERE year = ? ORDER BY month ASC",
+        )
+        .bind(year)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // UPSERT (business key: year + month)
+    pub async fn set_income(
+        pool: &SqlitePool,
+        year: i32,
+        month: u32,
+        amount: f64,
+    ) -> Result<MonthlyIncome, AppError> {
+        Self::validate_month(month)?;
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(AppError::validation(
+                "amount",
+                format!("Invalid income amount: {amount}"),
+            ));
+        }
+
+        sqlx::query_as::<_, MonthlyIncome>(
+            "INSERT INTO monthly_income (id, year, month, amount, updated_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (year, month) DO UPDATE SET
+                amount = excluded.amount,
+                updated_at = excluded.updated_at
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(year)
+        .bind(month)
+        .bind(amount)
+        .bind(chrono::Utc::now())
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // DELETE income
+    pub async fn delete_income(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM monthly_income WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    fn validate_month(month: u32) -> Result<(), AppError> {
+        if !(1..=12).contains(&month) {
+            return Err(AppError::validation(
+                "month",
+                format!("Invalid month: {month}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /**
+     * Contributions vs returns for every month in the net worth history.
+     * Accepts the same filters as the history; account filters also apply to contributions,
+     * so e.g. the investments sub-category shows its own contributions and returns.
+     * Without a filter, transfers between tracked accounts add nothing to contributions.
+     */
+    pub async fn get_report(
+        pool: &SqlitePool,
+        query: &NetWorthQuery,
+    ) -> Result<Vec<SavingsMonth>, AppError> {
+        let history = NetWorthService::query_history(pool, query).await?;
+        let Some(home_currency) = history.first().map(|point| point.currency.clone()) else {
+            return Ok(Vec::new());
+        };
+
+        let accounts: HashMap<String, Account> = AccountService::get_all(pool, true)
+            .await?
+            .into_iter()
+            .filter(|account| Self::matches(query, account))
+            .map(|account| (account.id.clone(), account))
+            .collect();
+        let converter = CurrencyConverter::load(pool).await?;
+
+        // (Account, Year, Month) -> amount in the account's currency
+        let entered: HashMap<(String, i32, u32), f64> =
+            sqlx::query_as::<_, Contribution>("SELECT * FROM contributions")
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|c| ((c.account_id, c.year, c.month), c.amount))
+                .collect();
+        // Ledger amounts move the account's balance; a liability's balance is debt, so its
+        // flows count against net worth (paying a mortgage from cash nets to zero)
+        let ledger: Vec<(String, i32, u32, f64)> = sqlx::query_as(
+            "SELECT account_id, CAST(strftime('%Y', date) AS INTEGER), CAST(strftime('%m', date) AS INTEGER), SUM(amount)
+             FROM transactions GROUP BY 1, 2, 3",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut flows: Vec<(String, i32, u32, f64, bool)> = entered
+            .iter()
+            .map(|((account_id, year, month), amount)| {
+                (account_id.clone(), *year, *month, *amount, false)
+            })
+            .collect();
+        flows.extend(
+            ledger
+                .into_iter()
+                .filter(|(account_id, year, month, _)| {
+                    !entered.contains_key(&(account_id.clone(), *year, *month))
+                })
+                .map(|(account_id, year, month, amount)| (account_id, year, month, amount, true)),
+        );
+
+        // (Year, Month) -> (contributions, inferred part, unconvertible currencies)
+        let mut totals: HashMap<(i32, u32), (f64, f64, BTreeSet<String>)> = HashMap::new();
+        for (account_id, year, month, amount, inferred) in flows {
+            let Some(account) = accounts.get(&account_id) else {
+                continue;
+            };
+            let total = totals.entry((year, month)).or_default();
+            let Some(conversion) =
+                converter.convert_rate(&account.currency, &home_currency, year, month)
+            else {
+                total.2.insert(account.currency.clone());
+                continue;
+            };
+            let sign = if inferred && account.account_type != "Asset" {
+                -1.0
+            } else {
+                1.0
+            };
+            let amount = sign * amount * conversion.rate;
+            total.0 += amount;
+            if inferred {
+                total.1 += amount;
+            }
+        }
+
+        let income: HashMap<(i32, u32), f64> =
+            sqlx::query_as::<_, MonthlyIncome>("SELECT * FROM monthly_income")
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|i| ((i.year, i.month), i.amount))
+                .collect();
+        let net_worth: HashMap<(i32, u32), f64> = history
+            .iter()
+            .map(|point| ((point.year, point.month), point.net_worth))
+            .collect();
+
+        Ok(history
+            .iter()
+            .map(|point| {
+                let (year, month) = (point.year, point.month);
+                let previous = if month == 1 {
+                    (year - 1, 12)
+                } else {
+                    (year, month - 1)
+                };
+                let net_worth_change = net_worth
+                    .get(&previous)
+                    .map(|previous| point.net_worth - previous);
+                let (contributions, inferred_contributions, missing_currencies) =
+                    totals.remove(&(year, month)).unwrap_or_default();
+                let income = income.get(&(year, month)).copied();
+
+                SavingsMonth {
+                    year,
+                    month,
+                    currency: home_currency.clone(),
+                    net_worth_change,
+                    contributions,
+                    inferred_contributions,
+                    returns: net_worth_change.map(|change| change - contributions),
+                    income,
+                    savings_rate: income
+                        .filter(|income| *income > 0.0)
+                        .map(|income| contributions / income * 100.0),
+                    missing_currencies: missing_currencies.into_iter().collect(),
+                }
+            })
+            .collect())
+    }
+
+    // The account filters of a net worth query
+    fn matches(query: &NetWorthQuery, account: &Account) -> bool {
+        query
+            .account_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&account.id))
+            && query
+                .account_type
+                .as_ref()
+                .is_none_or(|account_type| *account_type == account.account_type)
+            && query
+                .sub_category
+                .as_ref()
+                .is_none_or(|sub_category| account.sub_category.as_ref() == Some(sub_category))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{
+        balance_sheet::BalanceSheetService,
+        entry::EntryService,
+        transaction::{TransactionInput, TransactionService, TransferInput},
+        user_settings::UserSettingsService,
+    };
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn test_contributions_vs_returns() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let shares = AccountService::upsert(
+            &pool,
+            None,
+            "Shares".into(),
+            "Asset".into(),
+            "USD".into(),
+            Some("investments".into()),
+        )
+        .await
+        .expect("shares");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("cash");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+        for (account_id, month, amount) in [
+            (&shares.id, 1, 1000.0),
+            (&shares.id, 2, 1150.0),
+            (&cash.id, 1, 500.0),
+            (&cash.id, 2, 700.0),
+        ] {
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account_id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("entry");
+        }
+
+        SavingsService::set_contribution(&pool, shares.id.clone(), 2024, 2, 100.0)
+            .await
+            .expect("contribution");
+        // Entering it again replaces it
+        SavingsService::set_contribution(&pool, shares.id.clone(), 2024, 2, 120.0)
+            .await
+            .expect("contribution update");
+        assert_eq!(
+            SavingsService::get_contributions(&pool, 2024)
+                .await
+                .expect("contributions")
+                .len(),
+            1
+        );
+        // Cash has no contribution entered, so its transactions are used
+        TransactionService::upsert(
+            &pool,
+            None,
+            TransactionInput {
+                account_id: cash.id.clone(),
+                date: chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                amount: 200.0,
+                payee: None,
+                category: None,
+                memo: None,
+            },
+        )
+        .await
+        .expect("transaction");
+        SavingsService::set_income(&pool, 2024, 2, 1000.0)
+            .await
+            .expect("income");
+
+        let report = SavingsService::get_report(&pool, &NetWorthQuery::default())
+            .await
+            .expect("report");
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].net_worth_change, None);
+        assert_eq!(report[0].savings_rate, None);
+
+        let february = &report[1];
+        assert_eq!(february.net_worth_change, Some(350.0));
+        assert_eq!(february.contributions, 320.0);
+        assert_eq!(february.inferred_contributions, 200.0);
+        assert_eq!(february.returns, Some(30.0));
+        assert_eq!(february.savings_rate, Some(32.0));
+
+        // Narrowed to the investments, only their contribution counts
+        let investments = SavingsService::get_report(
+            &pool,
+            &NetWorthQuery {
+                sub_category: Some("investments".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("investments report");
+        assert_eq!(investments[1].contributions, 120.0);
+        assert_eq!(investments[1].returns, Some(30.0));
+
+        let invalid = SavingsService::set_income(&pool, 2024, 13, 10.0).await;
+        assert!(matches!(invalid, Err(AppError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_liability_flows_count_against_net_worth() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let cash = AccountService::upsert(
+            &pool,
+            None,
+            "Cash".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("cash");
+        let mortgage = AccountService::upsert(
+            &pool,
+            None,
+            "Mortgage".into(),
+            "Liability".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("mortgage");
+        let card = AccountService::upsert(
+            &pool,
+            None,
+            "Credit Card".into(),
+            "Liability".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("card");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+        for (account_id, month, amount) in [
+            (&cash.id, 1, 5000.0),
+            (&cash.id, 2, 4000.0),
+            (&mortgage.id, 1, 100_000.0),
+            (&mortgage.id, 2, 99_000.0),
+            (&card.id, 1, 0.0),
+            (&card.id, 2, 200.0),
+        ] {
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account_id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("entry");
+        }
+
+        // Paying 1000 off the mortgage from cash, and spending 200 on the card
+        for (account_id, amount) in [
+            (&cash.id, -1000.0),
+            (&mortgage.id, -1000.0),
+            (&card.id, 200.0),
+        ] {
+            TransactionService::upsert(
+                &pool,
+                None,
+                TransactionInput {
+                    account_id: account_id.clone(),
+                    date: chrono::NaiveDate::from_ymd_opt(2024, 2, 10).unwrap(),
+                    amount,
+                    payee: None,
+                    category: None,
+                    memo: None,
+                },
+            )
+            .await
+            .expect("transaction");
+        }
+
+        let report = SavingsService::get_report(&pool, &NetWorthQuery::default())
+            .await
+            .expect("report");
+        let february = &report[1];
+        assert_eq!(february.net_worth_change, Some(-200.0));
+        assert_eq!(february.contributions, -200.0);
+        assert_eq!(february.inferred_contributions, -200.0);
+        assert_eq!(february.returns, Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_transfers_between_tracked_accounts_are_not_contributions() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test User".into(), "USD".into(), "system".into())
+            .await
+            .expect("setup settings");
+        let checking = AccountService::upsert(
+            &pool,
+            None,
+            "Checking".into(),
+            "Asset".into(),
+            "USD".into(),
+            None,
+        )
+        .await
+        .expect("checking");
+        let brokerage = AccountService::upsert(
+            &pool,
+            None,
+            "Brokerage".into(),
+            "Asset".into(),
+            "USD".into(),
+            Some("investments".into()),
+        )
+        .await
+        .expect("brokerage");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+        for (account_id, month, amount) in [
+            (&checking.id, 1, 1000.0),
+            (&checking.id, 2, 500.0),
+            (&brokerage.id, 1, 0.0),
+            (&brokerage.id, 2, 520.0),
+        ] {
+            EntryService::upsert(
+                &pool,
+                sheet.id.clone(),
+                account_id.clone(),
+                month,
+                amount,
+                Default::default(),
+            )
+            .await
+            .expect("entry");
+        }
+        TransactionService::create_transfer(
+            &pool,
+            TransferInput {
+                from_account_id: checking.id.clone(),
+                to_account_id: brokerage.id.clone(),
+                date: chrono::NaiveDate::from_ymd_opt(2024, 2, 5).unwrap(),
+                amount: 500.0,
+                to_amount: None,
+                memo: None,
+            },
+        )
+        .await
+        .expect("transfer");
+
+        // Across every account the move saves nothing; only the growth is left
+        let report = SavingsService::get_report(&pool, &NetWorthQuery::default())
+            .await
+            .expect("report");
+        assert_eq!(report[1].net_worth_change, Some(20.0));
+        assert_eq!(report[1].contributions, 0.0);
+        assert_eq!(report[1].returns, Some(20.0));
+
+        // The brokerage on its own did receive it
+        let investments = SavingsService::get_report(
+            &pool,
+            &NetWorthQuery {
+                sub_category: Some("investments".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("investments report");
+        assert_eq!(investments[1].contributions, 500.0);
+        assert_eq!(investments[1].returns, Some(20.0));
+    }
+}
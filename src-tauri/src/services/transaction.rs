@@ -0,0 +1,688 @@
+use crate::error::AppError;
+use crate::models::Transaction;
+use crate::services::account::AccountService;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Sqlite, SqlitePool};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+// Differences below half a cent are rounding noise, not discrepancies
+const RECONCILIATION_TOLERANCE: f64 = 0.005;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInput {
+    pub account_id: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub payee: Option<String>,
+    pub category: Option<String>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferInput {
+    pub from_account_id: String,
+    pub to_account_id: String,
+    pub date: NaiveDate,
+    // Always positive; it leaves `from_account_id` and arrives in `to_account_id`
+    pub amount: f64,
+    // What arrives in `to_account_id`, in its currency. Required when the accounts' currencies
+    // differ; defaults to `amount` otherwise.
+    #[serde(default)]
+    pub to_amount: Option<f64>,
+    pub memo: Option<String>,
+}
+
+/**
+ * A month where the account's transactions don't add up to the change between
+ * its previous and current monthly entries.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationDiscrepancy {
+    pub account_id: String,
+    pub year: i32,
+    pub month: u32,
+    pub opening_balance: f64,
+    pub closing_balance: f64,
+    pub snapshot_change: f64,
+    pub transactions_total: f64,
+    // snapshot_change - transactions_total
+    pub difference: f64,
+}
+
+pub struct TransactionService;
+
+impl TransactionService {
+    // LIST (newest first)
+    pub async fn get_all(
+        pool: &SqlitePool,
+        account_id: Option<String>,
+    ) -> Result<Vec<Transaction>, AppError> {
+        sqlx::query_as::<_, Transaction>(
+            "SELECT * FROM transactions WHERE ($1 IS NULL OR account_id = $1) ORDER BY date DESC, created_at DESC",
+        )
+        .bind(account_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // READ
+    pub async fn get_by_id<'e, E>(executor: E, id: &str) -> Result<Option<Transaction>, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(executor)
+            .await
+            .map_err(AppError::from)
+    }
+
+    // UPSERT
+    // Editing one leg of a transfer keeps the other leg's date and (negated) amount in step.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        id: Option<String>,
+        input: TransactionInput,
+    ) -> Result<Transaction, AppError> {
+        if !input.amount.is_finite() {
+            return Err(AppError::validation(
+                "amount",
+                format!("Invalid amount: {}", input.amount),
+            ));
+        }
+        let Some(account) = AccountService::get_by_id(pool, input.account_id.clone()).await? else {
+            return Err(AppError::not_found("Account", input.account_id));
+        };
+
+        let mut tx = pool.begin().await?;
+        let now = chrono::Utc::now();
+
+        let existing = match &id {
+            Some(uid) => Self::get_by_id(&mut *tx, uid).await?,
+            None => None,
+        };
+
+        // Moving a transfer leg must leave a pair `create_transfer` would accept
+        if let Some(existing) = existing.as_ref().filter(|e| e.transfer_id.is_some()) {
+            let (other_account_id, other_currency, current_currency): (String, String, String) =
+                sqlx::query_as(
+                    "SELECT t.account_id, a.currency, (SELECT currency FROM accounts WHERE id = ?) FROM transactions t JOIN accounts a ON a.id = t.account_id WHERE t.id = ?",
+                )
+                .bind(&existing.account_id)
+                .bind(&existing.transfer_id)
+                .fetch_one(&mut *tx)
+                .await?;
+            if other_account_id == account.id {
+                return Err(AppError::validation(
+                    "accountId",
+                    "A transfer needs two different accounts",
+                ));
+            }
+            if current_currency == other_currency && account.currency != other_currency {
+                return Err(AppError::validation(
+                    "accountId",
+                    format!(
+                        "A transfer from {other_currency} to {} needs the amount that arrives, so record it again",
+                        account.currency
+                    ),
+                ));
+            }
+        }
+
+        let transaction = if let Some(existing) = existing {
+            let updated = sqlx::query_as::<_, Transaction>(
+                "UPDATE transactions SET account_id = ?, date = ?, amount = ?, payee = ?, category = ?, memo = ?, updated_at = ? WHERE id = ? RETURNING *",
+            )
+            .bind(&input.account_id)
+            .bind(input.date)
+            .bind(input.amount)
+            .bind(&input.payee)
+            .bind(&input.category)
+            .bind(&input.memo)
+            .bind(now)
+            .bind(&existing.id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if let Some(transfer_id) = &updated.transfer_id {
+                // A transfer across currencies keeps the other leg's amount, only its date moves
+                let (currencies,): (i64,) = sqlx::query_as(
+                    "SELECT COUNT(DISTINCT a.currency) FROM transactions t JOIN accounts a ON a.id = t.account_id WHERE t.id IN (?, ?)",
+                )
+                .bind(&updated.id)
+                .bind(transfer_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                sqlx::query(
+                    "UPDATE transactions SET date = ?, amount = COALESCE(?, amount), updated_at = ? WHERE id = ?",
+                )
+                .bind(updated.date)
+                .bind((currencies == 1).then_some(-updated.amount))
+                .bind(now)
+                .bind(transfer_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            updated
+        } else {
+            Self::insert(&mut *tx, &input, None).await?
+        };
+
+        tx.commit().await?;
+        Ok(transaction)
+    }
+
+    /**
+     * Records a transfer as two linked transactions: `-amount` on the source account
+     * and `+to_amount` (or `+amount`) on the destination, each pointing at the other via `transfer_id`.
+     */
+    pub async fn create_transfer(
+        pool: &SqlitePool,
+        input: TransferInput,
+    ) -> Result<Vec<Transaction>, AppError> {
+        if input.from_account_id == input.to_account_id {
+            return Err(AppError::validation(
+                "toAccountId",
+                "A transfer needs two different accounts",
+            ));
+        }
+        if !input.amount.is_finite() || input.amount <= 0.0 {
+            return Err(AppError::validation(
+                "amount",
+                format!("Invalid transfer amount: {}", input.amount),
+            ));
+        }
+        let mut currencies = Vec::with_capacity(2);
+        for account_id in [&input.from_account_id, &input.to_account_id] {
+            let account = AccountService::get_by_id(pool, account_id.clone())
+                .await?
+                .ok_or_else(|| AppError::not_found("Account", account_id.clone()))?;
+            currencies.push(account.currency);
+        }
+        let to_amount = match input.to_amount {
+            Some(to_amount) if !to_amount.is_finite() || to_amount <= 0.0 => {
+                return Err(AppError::validation(
+                    "toAmount",
+                    format!("Invalid transfer amount: {to_amount}"),
+                ));
+            }
+            Some(to_amount) if currencies[0] == currencies[1] && to_amount != input.amount => {
+                return Err(AppError::validation(
+                    "toAmount",
+                    format!(
+                        "Both accounts use {}, so the same amount has to arrive as leaves",
+                        currencies[0]
+                    ),
+                ));
+            }
+            Some(to_amount) => to_amount,
+            None if currencies[0] != currencies[1] => {
+                return Err(AppError::validation(
+                    "toAmount",
+                    format!(
+                        "A transfer from {} to {} needs the amount that arrives",
+                        currencies[0], currencies[1]
+                    ),
+                ));
+            }
+            None => input.amount,
+        };
+
+        let out_id = Uuid::new_v4().to_string();
+        let in_id = Uuid::new_v4().to_string();
+        let leg = |account_id: &str, amount: f64| TransactionInput {
+            account_id: account_id.to_string(),
+            date: input.date,
+            amount,
+            payee: None,
+            category: None,
+            memo: input.memo.clone(),
+        };
+
+        // The transfer_id foreign key is deferred, so each leg can reference the other up front
+        let mut tx = pool.begin().await?;
+        let outgoing = Self::insert_with_id(
+            &mut *tx,
+            &out_id,
+            &leg(&input.from_account_id, -input.amount),
+            Some(&in_id),
+        )
+        .await?;
+        let incoming = Self::insert_with_id(
+            &mut *tx,
+            &in_id,
+            &leg(&input.to_account_id, to_amount),
+            Some(&out_id),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(vec![outgoing, incoming])
+    }
+
+    async fn insert<'e, E>(
+        executor: E,
+        input: &TransactionInput,
+        transfer_id: Option<&str>,
+    ) -> Result<Transaction, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        Self::insert_with_id(executor, &Uuid::new_v4().to_string(), input, transfer_id).await
+    }
+
+    async fn insert_with_id<'e, E>(
+        executor: E,
+        id: &str,
+        input: &TransactionInput,
+        transfer_id: Option<&str>,
+    ) -> Result<Transaction, AppError>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let now = chrono::Utc::now();
+        sqlx::query_as::<_, Transaction>(
+            "INSERT INTO transactions (id, account_id, date, amount, payee, category, memo, transfer_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(id)
+        .bind(&input.account_id)
+        .bind(input.date)
+        .bind(input.amount)
+        .bind(&input.payee)
+        .bind(&input.category)
+        .bind(&input.memo)
+        .bind(transfer_id)
+        .bind(now)
+        .bind(now)
+        .fetch_one(executor)
+        .await
+        .map_err(AppError::from)
+    }
+
+    // DELETE
+    // Deleting either leg of a transfer deletes both.
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let transaction = Self::get_by_id(&mut *tx, &id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Transaction", id.clone()))?;
+
+        sqlx::query("DELETE FROM transactions WHERE id = ? OR id = ?")
+            .bind(&transaction.id)
+            .bind(&transaction.transfer_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /**
+     * For every account with transactions in `year`, compares each month's transaction total
+     * with the change between that month's entry and the previous month's entry.
+     * Months missing either entry can't be reconciled and are skipped.
+     */
+    pub async fn reconcile(
+        pool: &SqlitePool,
+        year: i32,
+    ) -> Result<Vec<ReconciliationDiscrepancy>, AppError> {
+        let (Some(start), Some(end)) = (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        ) else {
+            return Err(AppError::validation(
+                "year",
+                format!("Invalid year: {year}"),
+            ));
+        };
+
+        let totals: Vec<(String, u32, f64)> = sqlx::query_as(
+            "SELECT t.account_id, CAST(strftime('%m', t.date) AS INTEGER) AS month, SUM(t.amount) FROM transactions t JOIN accounts a ON a.id = t.account_id WHERE t.date >= ? AND t.date < ? AND a.deleted_at IS NULL GROUP BY t.account_id, month",
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+        let totals: HashMap<(String, u32), f64> = totals
+            .into_iter()
+            .map(|(account_id, month, total)| ((account_id, month), total))
+            .collect();
+        let account_ids: BTreeSet<&String> =
+            totals.keys().map(|(account_id, _)| account_id).collect();
+
+        // Include the previous December so January has an opening balance
+        let snapshots: Vec<(String, i32, u32, f64)> = sqlx::query_as(
+            "SELECT e.account_id, b.year, e.month, e.amount FROM entries e JOIN balance_sheets b ON b.id = e.balance_sheet_id WHERE b.year IN (?, ?) AND b.deleted_at IS NULL",
+        )
+        .bind(year - 1)
+        .bind(year)
+        .fetch_all(pool)
+        .await?;
+        let snapshots: HashMap<(String, i32, u32), f64> = snapshots
+            .into_iter()
+            .map(|(account_id, year, month, amount)| ((account_id, year, month), amount))
+            .collect();
+
+        let mut discrepancies = Vec::new();
+        for account_id in account_ids {
+            for month in 1..=12 {
+                let previous = if month == 1 {
+                    (year - 1, 12)
+                } else {
+                    (year, month - 1)
+                };
+                let opening = snapshots.get(&(account_id.clone(), previous.0, previous.1));
+                let closing = snapshots.get(&(account_id.clone(), year, month));
+                let (Some(&opening_balance), Some(&closing_balance)) = (opening, closing) else {
+                    continue;
+                };
+
+                let snapshot_change = closing_balance - opening_balance;
+                let transactions_total = totals
+                    .get(&(account_id.clone(), month))
+                    .copied()
+                    .unwrap_or(0.0);
+                let difference = snapshot_change - transactions_total;
+                if difference.abs() > RECONCILIATION_TOLERANCE {
+                    discrepancies.push(ReconciliationDiscrepancy {
+                        account_id: account_id.clone(),
+                        year,
+                        month,
+                        opening_balance,
+                        closing_balance,
+                        snapshot_change,
+                        transactions_total,
+                        difference,
+                    });
+                }
+            }
+        }
+
+        Ok(discrepancies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{balance_sheet::BalanceSheetService, entry::EntryService};
+    use crate::test_utils::setup_test_db;
+
+    async fn create_account(pool: &SqlitePool, name: &str) -> String {
+        AccountService::upsert(pool, None, name.into(), "Asset".into(), "NZD".into(), None)
+            .await
+            .expect("account")
+            .id
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transaction_crud() {
+        let pool = setup_test_db().await;
+        let account_id = create_account(&pool, "Checking").await;
+
+        let input = TransactionInput {
+            account_id: account_id.clone(),
+            date: date(2025, 1, 15),
+            amount: -42.5,
+            payee: Some("Grocer".into()),
+            category: Some("Groceries".into()),
+            memo: None,
+        };
+        let created = TransactionService::upsert(&pool, None, input.clone())
+            .await
+            .expect("create");
+        assert_eq!(created.date, date(2025, 1, 15));
+        assert_eq!(created.payee.as_deref(), Some("Grocer"));
+
+        let updated = TransactionService::upsert(
+            &pool,
+            Some(created.id.clone()),
+            TransactionInput {
+                amount: -40.0,
+                ..input.clone()
+            },
+        )
+        .await
+        .expect("update");
+        assert_eq!(updated.id, created.id);
+        assert_eq!(updated.amount, -40.0);
+
+        let missing_account = TransactionService::upsert(
+            &pool,
+            None,
+            TransactionInput {
+                account_id: "missing".into(),
+                ..input
+            },
+        )
+        .await;
+        assert_eq!(
+            missing_account.unwrap_err(),
+            AppError::not_found("Account", "missing")
+        );
+
+        TransactionService::delete(&pool, created.id)
+            .await
+            .expect("delete");
+        let all = TransactionService::get_all(&pool, None).await.expect("all");
+        assert!(all.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_creates_linked_legs() {
+        let pool = setup_test_db().await;
+        let checking = create_account(&pool, "Checking").await;
+        let savings = create_account(&pool, "Savings").await;
+
+        let legs = TransactionService::create_transfer(
+            &pool,
+            TransferInput {
+                from_account_id: checking.clone(),
+                to_account_id: savings.clone(),
+                date: date(2025, 2, 1),
+                amount: 500.0,
+                to_amount: None,
+                memo: Some("Monthly savings".into()),
+            },
+        )
+        .await
+        .expect("transfer");
+        assert_eq!(legs[0].amount, -500.0);
+        assert_eq!(legs[1].amount, 500.0);
+        assert_eq!(legs[0].transfer_id.as_ref(), Some(&legs[1].id));
+        assert_eq!(legs[1].transfer_id.as_ref(), Some(&legs[0].id));
+
+        // Editing one leg mirrors the other
+        TransactionService::upsert(
+            &pool,
+            Some(legs[1].id.clone()),
+            TransactionInput {
+                account_id: savings.clone(),
+                date: date(2025, 2, 2),
+                amount: 600.0,
+                payee: None,
+                category: None,
+                memo: None,
+            },
+        )
+        .await
+        .expect("edit leg");
+        let outgoing = TransactionService::get_all(&pool, Some(checking.clone()))
+            .await
+            .expect("checking");
+        assert_eq!(outgoing[0].amount, -600.0);
+        assert_eq!(outgoing[0].date, date(2025, 2, 2));
+
+        // A leg can't be moved onto the other leg's account, or into another currency
+        let euros = AccountService::upsert(
+            &pool,
+            None,
+            "Euros".into(),
+            "Asset".into(),
+            "EUR".into(),
+            None,
+        )
+        .await
+        .expect("account")
+        .id;
+        for account_id in [checking.clone(), euros] {
+            let moved = TransactionService::upsert(
+                &pool,
+                Some(legs[1].id.clone()),
+                TransactionInput {
+                    account_id,
+                    date: date(2025, 2, 2),
+                    amount: 600.0,
+                    payee: None,
+                    category: None,
+                    memo: None,
+                },
+            )
+            .await;
+            assert!(matches!(moved, Err(AppError::Validation { .. })));
+        }
+
+        TransactionService::delete(&pool, legs[0].id.clone())
+            .await
+            .expect("delete transfer");
+        let all = TransactionService::get_all(&pool, None).await.expect("all");
+        assert!(all.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_across_currencies_needs_the_arriving_amount() {
+        let pool = setup_test_db().await;
+        let checking = create_account(&pool, "Checking").await;
+        let euros = AccountService::upsert(
+            &pool,
+            None,
+            "Euros".into(),
+            "Asset".into(),
+            "EUR".into(),
+            None,
+        )
+        .await
+        .expect("account")
+        .id;
+        let transfer = |to_amount: Option<f64>| TransferInput {
+            from_account_id: checking.clone(),
+            to_account_id: euros.clone(),
+            date: date(2025, 2, 1),
+            amount: 500.0,
+            to_amount,
+            memo: None,
+        };
+
+        let missing = TransactionService::create_transfer(&pool, transfer(None)).await;
+        assert!(matches!(missing, Err(AppError::Validation { .. })));
+
+        let legs = TransactionService::create_transfer(&pool, transfer(Some(280.0)))
+            .await
+            .expect("transfer");
+        assert_eq!(legs[0].amount, -500.0);
+        assert_eq!(legs[1].amount, 280.0);
+
+        // Editing the outgoing leg doesn't rewrite the euros that arrived
+        TransactionService::upsert(
+            &pool,
+            Some(legs[0].id.clone()),
+            TransactionInput {
+                account_id: checking.clone(),
+                date: date(2025, 2, 3),
+                amount: -510.0,
+                payee: None,
+                category: None,
+                memo: None,
+            },
+        )
+        .await
+        .expect("edit leg");
+        let incoming = TransactionService::get_all(&pool, Some(euros))
+            .await
+            .expect("euros");
+        assert_eq!(incoming[0].amount, 280.0);
+        assert_eq!(incoming[0].date, date(2025, 2, 3));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_reports_mismatched_months() {
+        let pool = setup_test_db().await;
+        let checking = create_account(&pool, "Checking").await;
+        let sheet_2024 = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet 2024");
+        let sheet_2025 = BalanceSheetService::upsert(&pool, None, 2025)
+            .await
+            .expect("sheet 2025");
+
+        EntryService::upsert(
+            &pool,
+            sheet_2024.id,
+            checking.clone(),
+            12,
+            1000.0,
+            Default::default(),
+        )
+        .await
+        .expect("dec");
+        EntryService::upsert(
+            &pool,
+            sheet_2025.id.clone(),
+            checking.clone(),
+            1,
+            1100.0,
+            Default::default(),
+        )
+        .await
+        .expect("jan");
+        EntryService::upsert(
+            &pool,
+            sheet_2025.id,
+            checking.clone(),
+            2,
+            1000.0,
+            Default::default(),
+        )
+        .await
+        .expect("feb");
+
+        for (day, month, amount) in [(5, 1, 150.0), (20, 1, -50.0), (10, 2, -30.0)] {
+            TransactionService::upsert(
+                &pool,
+                None,
+                TransactionInput {
+                    account_id: checking.clone(),
+                    date: date(2025, month, day),
+                    amount,
+                    payee: None,
+                    category: None,
+                    memo: None,
+                },
+            )
+            .await
+            .expect("transaction");
+        }
+
+        let discrepancies = TransactionService::reconcile(&pool, 2025)
+            .await
+            .expect("reconcile");
+
+        // January: +100 in entries, +100 in transactions. February: -100 vs -30.
+        assert_eq!(discrepancies.len(), 1);
+        let february = &discrepancies[0];
+        assert_eq!(february.month, 2);
+        assert!((february.snapshot_change - -100.0).abs() < 0.001);
+        assert!((february.transactions_total - -30.0).abs() < 0.001);
+        assert!((february.difference - -70.0).abs() < 0.001);
+    }
+}
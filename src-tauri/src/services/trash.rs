@@ -0,0 +1,133 @@
+use crate::error::AppError;
+use crate::models::{Account, BalanceSheet, RetirementPlan};
+use crate::services::account::AccountService;
+use crate::services::balance_sheet::BalanceSheetService;
+use crate::services::retirement_plan::RetirementPlanService;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/**
+ * Everything that has been deleted but not yet purged, most recently deleted first.
+ * Trashed rows keep their entries and projections, so restoring one brings it back whole.
+ */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trash {
+    pub accounts: Vec<Account>,
+    pub balance_sheets: Vec<BalanceSheet>,
+    pub retirement_plans: Vec<RetirementPlan>,
+}
+
+pub struct TrashService;
+
+impl TrashService {
+    pub async fn get_all(pool: &SqlitePool) -> Result<Trash, AppError> {
+        Ok(Trash {
+            accounts: AccountService::get_trashed(pool).await?,
+            balance_sheets: BalanceSheetService::get_trashed(pool).await?,
+            retirement_plans: RetirementPlanService::get_trashed(pool).await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::entry::EntryService;
+    use crate::services::net_worth::NetWorthService;
+    use crate::services::user_settings::UserSettingsService;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn test_trash_restore_and_purge() {
+        let pool = setup_test_db().await;
+        UserSettingsService::upsert(&pool, "Test".into(), "NZD".into(), "light".into())
+            .await
+            .expect("settings");
+        let sheet = BalanceSheetService::upsert(&pool, None, 2024)
+            .await
+            .expect("sheet");
+        let account = AccountService::upsert(
+            &pool,
+            None,
+            "Bank".into(),
+            "Asset".into(),
+            "NZD".into(),
+            None,
+        )
+        .await
+        .expect("account");
+        EntryService::upsert(
+            &pool,
+            sheet.id.clone(),
+            account.id.clone(),
+            1,
+            500.0,
+            Default::default(),
+        )
+        .await
+        .expect("entry");
+
+        // Trashed rows disappear from reads but keep their entries
+        AccountService::delete(&pool, account.id.clone())
+            .await
+            .expect("trash account");
+        BalanceSheetService::delete(&pool, sheet.id.clone())
+            .await
+            .expect("trash sheet");
+        assert!(AccountService::get_all(&pool, true)
+            .await
+            .expect("accounts")
+            .is_empty());
+        assert!(BalanceSheetService::get_all(&pool)
+            .await
+            .expect("sheets")
+            .is_empty());
+        assert!(NetWorthService::get_history(&pool)
+            .await
+            .expect("history")
+            .is_empty());
+        assert_eq!(
+            EntryService::get_all(&pool).await.expect("entries").len(),
+            1
+        );
+
+        let trash = TrashService::get_all(&pool).await.expect("trash");
+        assert_eq!(trash.accounts.len(), 1);
+        assert_eq!(trash.balance_sheets.len(), 1);
+
+        // The trashed year and name are still taken
+        let taken = BalanceSheetService::upsert(&pool, None, 2024).await;
+        assert!(matches!(taken, Err(AppError::Conflict { .. })));
+
+        // Restoring brings the history back
+        AccountService::restore(&pool, account.id.clone())
+            .await
+            .expect("restore account");
+        BalanceSheetService::restore(&pool, sheet.id.clone())
+            .await
+            .expect("restore sheet");
+        let history = NetWorthService::get_history(&pool).await.expect("history");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].net_worth, 500.0);
+
+        // Only trashed rows can be purged, and purging cascades to entries
+        let live = AccountService::purge(&pool, account.id.clone()).await;
+        assert!(matches!(live, Err(AppError::NotFound { .. })));
+        AccountService::delete(&pool, account.id.clone())
+            .await
+            .expect("trash again");
+        AccountService::purge(&pool, account.id)
+            .await
+            .expect("purge");
+        assert!(TrashService::get_all(&pool)
+            .await
+            .expect("trash")
+            .accounts
+            .is_empty());
+        assert!(EntryService::get_all(&pool)
+            .await
+            .expect("entries")
+            .is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::models::UserSettings;
 use sqlx::SqlitePool;
 use uuid::Uuid;
@@ -6,21 +7,24 @@ pub struct UserSettingsService;
 
 impl UserSettingsService {
     // LIST
-    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<UserSettings>, String> {
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<UserSettings>, AppError> {
         sqlx::query_as::<_, UserSettings>("SELECT * FROM user_settings")
             .fetch_all(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
     }
 
     // READ
     #[allow(dead_code)]
-    pub async fn get_by_id(pool: &SqlitePool, id: String) -> Result<Option<UserSettings>, String> {
+    pub async fn get_by_id(
+        pool: &SqlitePool,
+        id: String,
+    ) -> Result<Option<UserSettings>, AppError> {
         sqlx::query_as::<_, UserSettings>("SELECT * FROM user_settings WHERE id = ?")
             .bind(id)
             .fetch_optional(pool)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(AppError::from)
     }
 
     // UPSERT
@@ -29,23 +33,26 @@ impl UserSettingsService {
         name: String,
         home_currency: String,
         theme: String,
-    ) -> Result<UserSettings, String> {
+    ) -> Result<UserSettings, AppError> {
         // Since we are enforcing a singleton pattern for user settings (one row),
         // we check if it exists first.
         let existing = Self::get_all(pool).await?;
 
         if let Some(settings) = existing.first() {
+            // Stored rates are keyed to the old home currency, so a change needs a resync
+            let home_currency_changed = settings.home_currency != home_currency;
+
             // Update
             let updated_record = sqlx::query_as::<_, UserSettings>(
-                "UPDATE user_settings SET name = $1, home_currency = $2, theme = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $4 RETURNING *"
+                "UPDATE user_settings SET name = $1, home_currency = $2, theme = $3, needs_exchange_sync = $4, updated_at = CURRENT_TIMESTAMP WHERE id = $5 RETURNING *"
             )
             .bind(name)
             .bind(home_currency)
             .bind(theme)
+            .bind(settings.needs_exchange_sync || home_currency_changed)
             .bind(&settings.id)
             .fetch_one(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
             Ok(updated_record)
         } else {
@@ -59,8 +66,7 @@ impl UserSettingsService {
             .bind(home_currency)
             .bind(theme)
             .fetch_one(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
             Ok(new_record)
         }
@@ -69,11 +75,11 @@ impl UserSettingsService {
     pub async fn set_exchange_sync_needed(
         pool: &SqlitePool,
         needs_exchange_sync: bool,
-    ) -> Result<UserSettings, String> {
+    ) -> Result<UserSettings, AppError> {
         let existing = Self::get_all(pool).await?;
         let settings = existing
             .first()
-            .ok_or_else(|| "User settings not found".to_string())?;
+            .ok_or_else(|| AppError::missing("User settings"))?;
 
         sqlx::query_as::<_, UserSettings>(
             "UPDATE user_settings SET needs_exchange_sync = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2 RETURNING *",
@@ -82,17 +88,42 @@ impl UserSettingsService {
         .bind(&settings.id)
         .fetch_one(pool)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+    }
+
+    pub async fn set_backup_retention(
+        pool: &SqlitePool,
+        backup_retention: i64,
+    ) -> Result<UserSettings, AppError> {
+        if backup_retention < 1 {
+            return Err(AppError::validation(
+                "backupRetention",
+                format!("Invalid backup retention: {backup_retention}"),
+            ));
+        }
+
+        let existing = Self::get_all(pool).await?;
+        let settings = existing
+            .first()
+            .ok_or_else(|| AppError::missing("User settings"))?;
+
+        sqlx::query_as::<_, UserSettings>(
+            "UPDATE user_settings SET backup_retention = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2 RETURNING *",
+        )
+        .bind(backup_retention)
+        .bind(&settings.id)
+        .fetch_one(pool)
+        .await
+        .map_err(AppError::from)
     }
 
     // DELETE
     #[allow(dead_code)]
-    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), String> {
+    pub async fn delete(pool: &SqlitePool, id: String) -> Result<(), AppError> {
         sqlx::query("DELETE FROM user_settings WHERE id = ?")
             .bind(id)
             .execute(pool)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
         Ok(())
     }
 }
@@ -139,12 +170,32 @@ mod tests {
         assert_eq!(updated.home_currency, "USD");
         assert_eq!(updated.theme, "dark");
         assert_eq!(updated.id, created.id); // ID should remain same
+        assert!(updated.needs_exchange_sync); // Home currency changed NZD -> USD
+
+        let synced = UserSettingsService::set_exchange_sync_needed(&pool, false)
+            .await
+            .expect("Failed to clear exchange sync flag");
+        assert!(!synced.needs_exchange_sync);
+        let renamed =
+            UserSettingsService::upsert(&pool, "Renamed User".into(), "USD".into(), "dark".into())
+                .await
+                .expect("Failed to rename");
+        assert!(!renamed.needs_exchange_sync);
 
         let flagged = UserSettingsService::set_exchange_sync_needed(&pool, true)
             .await
             .expect("Failed to set exchange sync flag");
         assert!(flagged.needs_exchange_sync);
 
+        assert_eq!(updated.backup_retention, 7);
+        let retention = UserSettingsService::set_backup_retention(&pool, 3)
+            .await
+            .expect("Failed to set backup retention");
+        assert_eq!(retention.backup_retention, 3);
+        assert!(UserSettingsService::set_backup_retention(&pool, 0)
+            .await
+            .is_err());
+
         // 5. Delete
         UserSettingsService::delete(&pool, created.id.clone())
             .await
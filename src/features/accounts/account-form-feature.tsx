@@ -12,6 +12,7 @@ import {
 } from "@/components/ui/select";
 import { api } from "@/lib/api";
 import { getSubCategoriesByAccountType } from "@/lib/categories";
+import { getErrorMessage } from "@/lib/errors";
 import {
   accountFormSchema,
   type AccountFormValues,
@@ -91,7 +92,7 @@ export function AccountFormFeature({
       onComplete();
     } catch (err) {
       console.error("Failed to save account:", err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     }
   };
 
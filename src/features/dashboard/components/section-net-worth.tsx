@@ -13,11 +13,20 @@ import { getNetWorthTrendChartData } from "@/lib/charts/net-worth-trend";
 import { getSubCategoryBreakdownChartData } from "@/lib/charts/sub-category-breakdown";
 import { ACCOUNTS_CHANGED_EVENT } from "@/lib/constants/events";
 import { DEFAULT_TIME_RANGE } from "@/lib/constants/time";
-import { calculateGrowth, getFilteredHistory } from "@/lib/net-worth";
+import {
+  calculateGrowth,
+  getFilteredHistory,
+  getRateWarningSummary,
+} from "@/lib/net-worth";
 import type { Entry } from "@/lib/types/balance-sheets";
 import { TimeRange } from "@/lib/types/time";
 import { useUserSettingsContext } from "@/providers/user-settings-provider";
-import { ChartColumnBigIcon, ChartLineIcon, ChartPieIcon } from "lucide-react";
+import {
+  ChartColumnBigIcon,
+  ChartLineIcon,
+  ChartPieIcon,
+  TriangleAlertIcon,
+} from "lucide-react";
 import { useCallback, useEffect, useMemo, useState } from "react";
 import { NetWorthKPIs } from "./net-worth-kpis";
 
@@ -90,6 +99,11 @@ export function SectionNetWorth() {
   const startPoint =
     filteredHistory.length > 0 ? filteredHistory[0] : undefined;
 
+  const rateWarnings = useMemo(
+    () => getRateWarningSummary(filteredHistory),
+    [filteredHistory],
+  );
+
   const trendChartData = useMemo(
     () => getNetWorthTrendChartData(filteredHistory),
     [filteredHistory],
@@ -175,6 +189,21 @@ export function SectionNetWorth() {
         </Tabs>
       </div>
 
+      {(rateWarnings.estimatedMonths > 0 ||
+        rateWarnings.missingMonths > 0) && (
+        <div className="flex items-start gap-2 rounded-lg border border-amber-500/40 bg-amber-500/10 px-3 py-2 text-sm">
+          <TriangleAlertIcon className="mt-0.5 h-4 w-4 shrink-0 text-amber-500" />
+          <span>
+            Exchange rates for {rateWarnings.currencies.join(", ")} are
+            incomplete.{" "}
+            {rateWarnings.estimatedMonths > 0 &&
+              `${rateWarnings.estimatedMonths} month(s) use the nearest earlier rate. `}
+            {rateWarnings.missingMonths > 0 &&
+              `${rateWarnings.missingMonths} month(s) exclude balances with no rate.`}
+          </span>
+        </div>
+      )}
+
       {/* KPI Cards */}
       <NetWorthKPIs
         currentNetWorth={currentNetWorth}
@@ -24,6 +24,7 @@ import {
   useCreateRetirementPlan,
   useRetirementPlans,
 } from "@/hooks/use-retirement";
+import { getErrorMessage } from "@/lib/errors";
 import {
   getScenarioLimitMessage,
   isScenarioLimitReached,
@@ -171,10 +172,10 @@ export function InputForm({
     } catch (error) {
       setSaveNotice({
         type: "error",
-        message:
-          error instanceof Error
-            ? error.message
-            : "Unable to save the scenario right now.",
+        message: getErrorMessage(
+          error,
+          "Unable to save the scenario right now.",
+        ),
       });
     }
   };
@@ -10,6 +10,7 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { api } from "@/lib/api";
+import { getErrorMessage } from "@/lib/errors";
 import type { Theme } from "@/lib/types/theme";
 import {
   getUserSettingsFormDefaults,
@@ -68,7 +69,7 @@ export function UserSettingsFormFeature({
       onComplete();
     } catch (error) {
       console.error("Failed to save settings:", error);
-      setError(error instanceof Error ? error.message : String(error));
+      setError(getErrorMessage(error));
     }
   };
 
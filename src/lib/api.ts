@@ -1,16 +1,66 @@
 import { invoke } from "@tauri-apps/api/core";
 import { COMMANDS } from "@/lib/constants/commands";
-import type { NetWorthDataPoint } from "@/lib/types/net-worth";
+import type {
+  NetWorthBreakdown,
+  NetWorthDataPoint,
+  NetWorthQuery,
+  UnconvertibleMonth,
+} from "@/lib/types/net-worth";
 import type { Account } from "@/lib/types/accounts";
-import type { BalanceSheet, Entry } from "@/lib/types/balance-sheets";
-import type { CurrencyRate } from "@/lib/types/currency-rates";
+import type { GrowthReport } from "@/lib/types/growth";
+import type {
+  AuditEntityType,
+  AuditLogEntry,
+  RevertEntryResult,
+} from "@/lib/types/audit";
+import type {
+  ArchiveImportReport,
+  ConflictMode,
+  DatabaseArchive,
+} from "@/lib/types/archive";
+import type { BackupInfo } from "@/lib/types/backups";
+import type {
+  BalanceSheet,
+  CarryForwardOptions,
+  Entry,
+  EntryCell,
+  EntryImportReport,
+  EntryProvenance,
+  EntryStatus,
+} from "@/lib/types/balance-sheets";
+import type {
+  Budget,
+  BudgetCategory,
+  BudgetReport,
+} from "@/lib/types/budgets";
+import type {
+  Contribution,
+  MonthlyIncome,
+  SavingsMonth,
+} from "@/lib/types/savings";
+import type {
+  CurrencyRate,
+  RateImportReport,
+} from "@/lib/types/currency-rates";
 import type { OnboardingStep } from "@/lib/types/onboarding";
+import type {
+  DraftEntry,
+  RecurringRule,
+  RecurringRuleInput,
+} from "@/lib/types/recurring-rules";
 import type {
   RetirementPlan,
   ReturnScenario,
   RetirementProjection,
   RetirementPlanProjection,
 } from "@/lib/types/retirement";
+import type {
+  ReconciliationDiscrepancy,
+  Transaction,
+  TransactionInput,
+  TransferInput,
+} from "@/lib/types/transactions";
+import type { Trash } from "@/lib/types/trash";
 import { UserSettings } from "@/lib/types/user-settings";
 
 // API Adapter
@@ -86,9 +136,15 @@ export const api = {
     return await invoke(COMMANDS.GET_BALANCE_SHEETS);
   },
 
-  createBalanceSheet: async (year: number): Promise<BalanceSheet> => {
+  createBalanceSheet: async (
+    year: number,
+    carryForward?: CarryForwardOptions,
+  ): Promise<BalanceSheet> => {
     // Note: Rust command uses i32, JS number is fine
-    return await invoke(COMMANDS.CREATE_BALANCE_SHEET, { year });
+    return await invoke(COMMANDS.CREATE_BALANCE_SHEET, {
+      year,
+      carryForward: carryForward ?? null,
+    });
   },
 
   deleteBalanceSheet: async (id: string): Promise<void> => {
@@ -96,21 +152,253 @@ export const api = {
   },
 
   // Entries
-  getEntries: async (balanceSheetId: string): Promise<Entry[]> => {
-    return await invoke(COMMANDS.GET_ENTRIES, { balanceSheetId });
+  getEntries: async (
+    balanceSheetId: string,
+    status?: EntryStatus,
+  ): Promise<Entry[]> => {
+    return await invoke(COMMANDS.GET_ENTRIES, {
+      balanceSheetId,
+      status: status ?? null,
+    });
   },
 
+  // Without provenance the entry is saved as confirmed
   upsertEntry: async (
     balanceSheetId: string,
     accountId: string,
     month: number,
     amount: number,
+    provenance?: EntryProvenance,
   ): Promise<Entry> => {
     return await invoke(COMMANDS.UPSERT_ENTRY, {
       balanceSheetId,
       accountId,
       month,
       amount,
+      provenance: provenance ?? null,
+    });
+  },
+
+  upsertEntries: async (
+    balanceSheetId: string,
+    cells: EntryCell[],
+  ): Promise<Entry[]> => {
+    return await invoke(COMMANDS.UPSERT_ENTRIES, { balanceSheetId, cells });
+  },
+
+  deleteEntry: async (id: string): Promise<void> => {
+    await invoke(COMMANDS.DELETE_ENTRY, { id });
+  },
+
+  clearMonth: async (balanceSheetId: string, month: number): Promise<number> => {
+    return await invoke(COMMANDS.CLEAR_MONTH, { balanceSheetId, month });
+  },
+
+  clearAccountYear: async (
+    balanceSheetId: string,
+    accountId: string,
+  ): Promise<number> => {
+    return await invoke(COMMANDS.CLEAR_ACCOUNT_YEAR, {
+      balanceSheetId,
+      accountId,
+    });
+  },
+
+  importEntriesCsv: async (
+    contents: string,
+    dryRun: boolean,
+  ): Promise<EntryImportReport> => {
+    return await invoke(COMMANDS.IMPORT_ENTRIES_CSV, { contents, dryRun });
+  },
+
+  // Omit `month` to confirm every estimated entry in the sheet
+  confirmEntries: async (
+    balanceSheetId: string,
+    month?: number,
+  ): Promise<number> => {
+    return await invoke(COMMANDS.CONFIRM_ENTRIES, {
+      balanceSheetId,
+      month: month ?? null,
+    });
+  },
+
+  // Change History
+  getEntryHistory: async (
+    accountId: string,
+    year: number,
+    month: number,
+  ): Promise<AuditLogEntry[]> => {
+    return await invoke(COMMANDS.GET_ENTRY_HISTORY, {
+      accountId,
+      year,
+      month,
+    });
+  },
+
+  getAuditHistory: async (
+    entityType: AuditEntityType,
+    entityId: string,
+  ): Promise<AuditLogEntry[]> => {
+    return await invoke(COMMANDS.GET_AUDIT_HISTORY, { entityType, entityId });
+  },
+
+  revertEntry: async (auditId: string): Promise<RevertEntryResult> => {
+    return await invoke(COMMANDS.REVERT_ENTRY, { auditId });
+  },
+
+  // Recurring Rules
+  getRecurringRules: async (): Promise<RecurringRule[]> => {
+    return await invoke(COMMANDS.GET_RECURRING_RULES);
+  },
+
+  upsertRecurringRule: async (
+    id: string | null,
+    rule: RecurringRuleInput,
+  ): Promise<RecurringRule> => {
+    return await invoke(COMMANDS.UPSERT_RECURRING_RULE, { id, rule });
+  },
+
+  deleteRecurringRule: async (id: string): Promise<void> => {
+    await invoke(COMMANDS.DELETE_RECURRING_RULE, { id });
+  },
+
+  // Omit `month` to draft the whole balance sheet year
+  previewRecurringEntries: async (
+    balanceSheetId: string,
+    month?: number,
+  ): Promise<DraftEntry[]> => {
+    return await invoke(COMMANDS.PREVIEW_RECURRING_ENTRIES, {
+      balanceSheetId,
+      month: month ?? null,
+    });
+  },
+
+  applyRecurringEntries: async (
+    balanceSheetId: string,
+    month: number | undefined,
+    overwrite: boolean,
+  ): Promise<Entry[]> => {
+    return await invoke(COMMANDS.APPLY_RECURRING_ENTRIES, {
+      balanceSheetId,
+      month: month ?? null,
+      overwrite,
+    });
+  },
+
+  // Transactions
+  getTransactions: async (accountId?: string): Promise<Transaction[]> => {
+    return await invoke(COMMANDS.GET_TRANSACTIONS, {
+      accountId: accountId ?? null,
+    });
+  },
+
+  upsertTransaction: async (
+    id: string | null,
+    transaction: TransactionInput,
+  ): Promise<Transaction> => {
+    return await invoke(COMMANDS.UPSERT_TRANSACTION, { id, transaction });
+  },
+
+  createTransfer: async (transfer: TransferInput): Promise<Transaction[]> => {
+    return await invoke(COMMANDS.CREATE_TRANSFER, { transfer });
+  },
+
+  deleteTransaction: async (id: string): Promise<void> => {
+    await invoke(COMMANDS.DELETE_TRANSACTION, { id });
+  },
+
+  reconcileTransactions: async (
+    year: number,
+  ): Promise<ReconciliationDiscrepancy[]> => {
+    return await invoke(COMMANDS.RECONCILE_TRANSACTIONS, { year });
+  },
+
+  // Budgets
+  getBudgetCategories: async (): Promise<BudgetCategory[]> => {
+    return await invoke(COMMANDS.GET_BUDGET_CATEGORIES);
+  },
+
+  upsertBudgetCategory: async (
+    id: string | null,
+    name: string,
+    rollover: boolean,
+  ): Promise<BudgetCategory> => {
+    return await invoke(COMMANDS.UPSERT_BUDGET_CATEGORY, {
+      id,
+      name,
+      rollover,
+    });
+  },
+
+  deleteBudgetCategory: async (id: string): Promise<void> => {
+    await invoke(COMMANDS.DELETE_BUDGET_CATEGORY, { id });
+  },
+
+  getBudgets: async (year: number, month: number): Promise<Budget[]> => {
+    return await invoke(COMMANDS.GET_BUDGETS, { year, month });
+  },
+
+  setBudget: async (
+    categoryId: string,
+    year: number,
+    month: number,
+    amount: number,
+  ): Promise<Budget> => {
+    return await invoke(COMMANDS.SET_BUDGET, {
+      categoryId,
+      year,
+      month,
+      amount,
+    });
+  },
+
+  rolloverBudgets: async (year: number, month: number): Promise<Budget[]> => {
+    return await invoke(COMMANDS.ROLLOVER_BUDGETS, { year, month });
+  },
+
+  getBudgetReport: async (
+    year: number,
+    month: number,
+  ): Promise<BudgetReport> => {
+    return await invoke(COMMANDS.GET_BUDGET_REPORT, { year, month });
+  },
+
+  // Savings
+  getContributions: async (year: number): Promise<Contribution[]> => {
+    return await invoke(COMMANDS.GET_CONTRIBUTIONS, { year });
+  },
+  setContribution: async (
+    accountId: string,
+    year: number,
+    month: number,
+    amount: number,
+  ): Promise<Contribution> => {
+    return await invoke(COMMANDS.SET_CONTRIBUTION, {
+      accountId,
+      year,
+      month,
+      amount,
+    });
+  },
+  deleteContribution: async (id: string): Promise<void> => {
+    return await invoke(COMMANDS.DELETE_CONTRIBUTION, { id });
+  },
+  getIncome: async (year: number): Promise<MonthlyIncome[]> => {
+    return await invoke(COMMANDS.GET_INCOME, { year });
+  },
+  setIncome: async (
+    year: number,
+    month: number,
+    amount: number,
+  ): Promise<MonthlyIncome> => {
+    return await invoke(COMMANDS.SET_INCOME, { year, month, amount });
+  },
+  deleteIncome: async (id: string): Promise<void> => {
+    return await invoke(COMMANDS.DELETE_INCOME, { id });
+  },
+  getSavingsReport: async (query?: NetWorthQuery): Promise<SavingsMonth[]> => {
+    return await invoke(COMMANDS.GET_SAVINGS_REPORT, {
+      query: query ?? null,
     });
   },
 
@@ -123,6 +411,16 @@ export const api = {
     return await invoke(COMMANDS.SYNC_EXCHANGE_RATES);
   },
 
+  importCurrencyRatesCsv: async (
+    contents: string,
+    dryRun: boolean,
+  ): Promise<RateImportReport> => {
+    return await invoke(COMMANDS.IMPORT_CURRENCY_RATES_CSV, {
+      contents,
+      dryRun,
+    });
+  },
+
   upsertCurrencyRate: async (
     id: string | null,
     fromCurrency: string,
@@ -148,12 +446,31 @@ export const api = {
   },
 
   // Net Worth
-  getNetWorthHistory: async (): Promise<NetWorthDataPoint[]> => {
-    return await invoke(COMMANDS.GET_NET_WORTH_HISTORY);
+  getNetWorthHistory: async (
+    query?: NetWorthQuery,
+  ): Promise<NetWorthDataPoint[]> => {
+    return await invoke(COMMANDS.GET_NET_WORTH_HISTORY, {
+      query: query ?? null,
+    });
+  },
+  getNetWorthBreakdown: async (
+    query?: NetWorthQuery,
+  ): Promise<NetWorthBreakdown[]> => {
+    return await invoke(COMMANDS.GET_NET_WORTH_BREAKDOWN, {
+      query: query ?? null,
+    });
+  },
+  getGrowthReport: async (query?: NetWorthQuery): Promise<GrowthReport> => {
+    return await invoke(COMMANDS.GET_GROWTH_REPORT, {
+      query: query ?? null,
+    });
   },
   getLatestNetWorth: async (): Promise<NetWorthDataPoint | null> => {
     return await invoke(COMMANDS.GET_LATEST_NET_WORTH);
   },
+  getUnconvertibleMonths: async (): Promise<UnconvertibleMonth[]> => {
+    return await invoke(COMMANDS.GET_UNCONVERTIBLE_MONTHS);
+  },
 
   // Retirement
   getRetirementPlans: async (): Promise<RetirementPlan[]> => {
@@ -200,6 +517,67 @@ export const api = {
     return await invoke(COMMANDS.GET_RETIREMENT_PLAN_PROJECTIONS, { planId });
   },
 
+  // Trash
+  getTrash: async (): Promise<Trash> => {
+    return await invoke(COMMANDS.GET_TRASH);
+  },
+
+  restoreAccount: async (id: string): Promise<Account> => {
+    return await invoke(COMMANDS.RESTORE_ACCOUNT, { id });
+  },
+
+  purgeAccount: async (id: string): Promise<void> => {
+    await invoke(COMMANDS.PURGE_ACCOUNT, { id });
+  },
+
+  restoreBalanceSheet: async (id: string): Promise<BalanceSheet> => {
+    return await invoke(COMMANDS.RESTORE_BALANCE_SHEET, { id });
+  },
+
+  purgeBalanceSheet: async (id: string): Promise<void> => {
+    await invoke(COMMANDS.PURGE_BALANCE_SHEET, { id });
+  },
+
+  restoreRetirementPlan: async (id: string): Promise<RetirementPlan> => {
+    return await invoke(COMMANDS.RESTORE_RETIREMENT_PLAN, { id });
+  },
+
+  purgeRetirementPlan: async (id: string): Promise<void> => {
+    await invoke(COMMANDS.PURGE_RETIREMENT_PLAN, { id });
+  },
+
+  // Archive
+  exportAll: async (): Promise<DatabaseArchive> => {
+    return await invoke(COMMANDS.EXPORT_ALL);
+  },
+
+  importAll: async (
+    archive: DatabaseArchive,
+    mode: ConflictMode,
+  ): Promise<ArchiveImportReport> => {
+    return await invoke(COMMANDS.IMPORT_ALL, { archive, mode });
+  },
+
+  // Backups
+  listBackups: async (): Promise<BackupInfo[]> => {
+    return await invoke(COMMANDS.LIST_BACKUPS);
+  },
+
+  createBackup: async (): Promise<BackupInfo> => {
+    return await invoke(COMMANDS.CREATE_BACKUP);
+  },
+
+  // The app restarts once the backup is staged
+  restoreBackup: async (fileName: string): Promise<void> => {
+    await invoke(COMMANDS.RESTORE_BACKUP, { fileName });
+  },
+
+  updateBackupRetention: async (
+    backupRetention: number,
+  ): Promise<UserSettings> => {
+    return await invoke(COMMANDS.UPDATE_BACKUP_RETENTION, { backupRetention });
+  },
+
   // Onboarding
   getOnboardingStatus: async (): Promise<OnboardingStep[]> => {
     return await invoke(COMMANDS.GET_ONBOARDING_STATUS);
@@ -22,16 +22,63 @@ export const COMMANDS = {
   // Entries
   GET_ENTRIES: "get_entries",
   UPSERT_ENTRY: "upsert_entry",
+  UPSERT_ENTRIES: "upsert_entries",
+  DELETE_ENTRY: "delete_entry",
+  CLEAR_MONTH: "clear_month",
+  CLEAR_ACCOUNT_YEAR: "clear_account_year",
+  IMPORT_ENTRIES_CSV: "import_entries_csv",
+  CONFIRM_ENTRIES: "confirm_entries",
+
+  // Change History
+  GET_ENTRY_HISTORY: "get_entry_history",
+  GET_AUDIT_HISTORY: "get_audit_history",
+  REVERT_ENTRY: "revert_entry",
+
+  // Recurring Rules
+  GET_RECURRING_RULES: "get_recurring_rules",
+  UPSERT_RECURRING_RULE: "upsert_recurring_rule",
+  DELETE_RECURRING_RULE: "delete_recurring_rule",
+  PREVIEW_RECURRING_ENTRIES: "preview_recurring_entries",
+  APPLY_RECURRING_ENTRIES: "apply_recurring_entries",
+
+  // Transactions
+  GET_TRANSACTIONS: "get_transactions",
+  UPSERT_TRANSACTION: "upsert_transaction",
+  CREATE_TRANSFER: "create_transfer",
+  DELETE_TRANSACTION: "delete_transaction",
+  RECONCILE_TRANSACTIONS: "reconcile_transactions",
+
+  // Budgets
+  GET_BUDGET_CATEGORIES: "get_budget_categories",
+  UPSERT_BUDGET_CATEGORY: "upsert_budget_category",
+  DELETE_BUDGET_CATEGORY: "delete_budget_category",
+  GET_BUDGETS: "get_budgets",
+  SET_BUDGET: "set_budget",
+  ROLLOVER_BUDGETS: "rollover_budgets",
+  GET_BUDGET_REPORT: "get_budget_report",
+
+  // Savings
+  GET_CONTRIBUTIONS: "get_contributions",
+  SET_CONTRIBUTION: "set_contribution",
+  DELETE_CONTRIBUTION: "delete_contribution",
+  GET_INCOME: "get_income",
+  SET_INCOME: "set_income",
+  DELETE_INCOME: "delete_income",
+  GET_SAVINGS_REPORT: "get_savings_report",
 
   // Currency Rates
   GET_CURRENCY_RATES: "get_currency_rates",
   SYNC_EXCHANGE_RATES: "sync_exchange_rates",
+  IMPORT_CURRENCY_RATES_CSV: "import_currency_rates_csv",
   UPSERT_CURRENCY_RATE: "upsert_currency_rate",
   DELETE_CURRENCY_RATE: "delete_currency_rate",
 
   // Net Worth
   GET_NET_WORTH_HISTORY: "get_net_worth_history",
+  GET_NET_WORTH_BREAKDOWN: "get_net_worth_breakdown",
+  GET_GROWTH_REPORT: "get_growth_report",
   GET_LATEST_NET_WORTH: "get_latest_net_worth",
+  GET_UNCONVERTIBLE_MONTHS: "get_unconvertible_months",
 
   // Retirement
   CREATE_RETIREMENT_PLAN: "create_retirement_plan",
@@ -42,6 +89,25 @@ export const COMMANDS = {
   CALCULATE_RETIREMENT_PROJECTION: "calculate_retirement_projection",
   GET_RETIREMENT_PLAN_PROJECTIONS: "get_retirement_plan_projections",
 
+  // Trash
+  GET_TRASH: "get_trash",
+  RESTORE_ACCOUNT: "restore_account",
+  PURGE_ACCOUNT: "purge_account",
+  RESTORE_BALANCE_SHEET: "restore_balance_sheet",
+  PURGE_BALANCE_SHEET: "purge_balance_sheet",
+  RESTORE_RETIREMENT_PLAN: "restore_retirement_plan",
+  PURGE_RETIREMENT_PLAN: "purge_retirement_plan",
+
+  // Archive
+  EXPORT_ALL: "export_all",
+  IMPORT_ALL: "import_all",
+
+  // Backups
+  LIST_BACKUPS: "list_backups",
+  CREATE_BACKUP: "create_backup",
+  RESTORE_BACKUP: "restore_backup",
+  UPDATE_BACKUP_RETENTION: "update_backup_retention",
+
   // Onboarding
   GET_ONBOARDING_STATUS: "get_onboarding_status",
   COMPLETE_ONBOARDING_STEP: "complete_onboarding_step",
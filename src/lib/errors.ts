@@ -0,0 +1,25 @@
+import type { AppError, AppErrorCode } from "@/lib/types/errors";
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    "code" in error &&
+    "message" in error &&
+    typeof (error as AppError).message === "string"
+  );
+}
+
+export function hasErrorCode(error: unknown, code: AppErrorCode): boolean {
+  return isAppError(error) && error.code === code;
+}
+
+export function getErrorMessage(error: unknown, fallback?: string): string {
+  if (isAppError(error) || error instanceof Error) {
+    return error.message;
+  }
+  if (typeof error === "string") {
+    return error;
+  }
+  return fallback ?? String(error);
+}
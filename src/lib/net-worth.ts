@@ -54,3 +54,23 @@ export function calculateGrowth(
   const percentage = (value / Math.abs(previous)) * 100;
   return { value, percentage };
 }
+
+export function getRateWarningSummary(
+  history: NetWorthDataPoint[],
+): { estimatedMonths: number; missingMonths: number; currencies: string[] } {
+  const currencies = new Set<string>();
+  let estimatedMonths = 0;
+  let missingMonths = 0;
+
+  for (const point of history) {
+    if (point.rateQuality === "estimated") estimatedMonths += 1;
+    if (point.rateQuality === "missing") missingMonths += 1;
+    point.warnings.forEach((warning) => currencies.add(warning.currency));
+  }
+
+  return {
+    estimatedMonths,
+    missingMonths,
+    currencies: [...currencies].sort(),
+  };
+}
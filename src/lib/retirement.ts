@@ -1,3 +1,4 @@
+import { getErrorMessage, hasErrorCode } from "@/lib/errors";
 import {
   RETIREMENT_PLAN_PROJECTION_BUFFER_DOLLARS,
   SCENARIO_LIMIT,
@@ -30,14 +31,12 @@ export function getProjectionErrorKind(
     return null;
   }
 
-  const message =
-    error instanceof Error
-      ? error.message
-      : typeof error === "string"
-        ? error
-        : String(error);
+  const message = getErrorMessage(error);
 
-  if (message.toLowerCase().includes("not achievable")) {
+  if (
+    hasErrorCode(error, "VALIDATION") &&
+    message.toLowerCase().includes("not achievable")
+  ) {
     return "notAchievable";
   }
 
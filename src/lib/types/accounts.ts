@@ -9,4 +9,6 @@ export interface Account {
   sortOrder: number;
   isArchived: boolean;
   createdAt: string;
+  // Set while the account is in the trash
+  deletedAt: string | null;
 }
@@ -0,0 +1,47 @@
+import type { Account } from "@/lib/types/accounts";
+import type { AuditLogEntry } from "@/lib/types/audit";
+import type { BalanceSheet, Entry } from "@/lib/types/balance-sheets";
+import type { Budget, BudgetCategory } from "@/lib/types/budgets";
+import type { CurrencyRate } from "@/lib/types/currency-rates";
+import type { RecurringRule } from "@/lib/types/recurring-rules";
+import type {
+  RetirementPlan,
+  RetirementPlanProjection,
+} from "@/lib/types/retirement";
+import type { Contribution, MonthlyIncome } from "@/lib/types/savings";
+import type { Transaction } from "@/lib/types/transactions";
+import type { UserSettings } from "@/lib/types/user-settings";
+
+export type ConflictMode = "replace" | "merge" | "abort";
+
+export interface DatabaseArchive {
+  formatVersion: number;
+  schemaVersion: number;
+  exportedAt: string;
+  userSettings: UserSettings[];
+  accounts: Account[];
+  balanceSheets: BalanceSheet[];
+  entries: Entry[];
+  transactions: Transaction[];
+  recurringRules: RecurringRule[];
+  budgetCategories: BudgetCategory[];
+  budgets: Budget[];
+  contributions: Contribution[];
+  monthlyIncome: MonthlyIncome[];
+  currencyRates: CurrencyRate[];
+  retirementPlans: RetirementPlan[];
+  retirementPlanProjections: RetirementPlanProjection[];
+  auditLog: AuditLogEntry[];
+}
+
+export interface ArchiveTableReport {
+  table: string;
+  inserted: number;
+  skipped: number;
+}
+
+export interface ArchiveImportReport {
+  mode: ConflictMode;
+  schemaVersion: number;
+  tables: ArchiveTableReport[];
+}
@@ -0,0 +1,29 @@
+import type { Entry } from "@/lib/types/balance-sheets";
+
+export type AuditEntityType =
+  | "entry"
+  | "account"
+  | "currency_rate"
+  | "retirement_plan";
+
+export type AuditAction = "insert" | "update" | "delete";
+
+export interface AuditLogEntry {
+  id: string;
+  entityType: AuditEntityType;
+  entityId: string;
+  action: AuditAction;
+  // JSON snapshots of the row before and after the change
+  oldValue: string | null;
+  newValue: string | null;
+  // The command that made the change, when known
+  origin: string | null;
+  // Set for entries only
+  accountId: string | null;
+  year: number | null;
+  month: number | null;
+  createdAt: string;
+}
+
+// Reverting an insert removes the entry, so there's nothing to return
+export type RevertEntryResult = Entry | null;
@@ -0,0 +1,6 @@
+export interface BackupInfo {
+  fileName: string;
+  reason: string;
+  createdAt: string;
+  sizeBytes: number;
+}
@@ -2,6 +2,8 @@ export interface BalanceSheet {
   id: string;
   year: number;
   createdAt: string;
+  // Set while the sheet is in the trash
+  deletedAt: string | null;
 }
 
 export interface Entry {
@@ -11,6 +13,38 @@ export interface Entry {
   month: number;
   amount: number;
   updatedAt: string;
+  status: EntryStatus;
+  // Where the value came from, e.g. "csv_import", "recurring_rule", "carry_forward"
+  source: string | null;
+  note: string | null;
+}
+
+export type EntryStatus =
+  | "confirmed"
+  | "estimated"
+  | "imported"
+  | "carried_forward";
+
+export interface EntryProvenance {
+  status: EntryStatus;
+  source?: string | null;
+  note?: string | null;
+}
+
+// One cell of the balance sheet grid, saved in bulk with `upsertEntries`
+export interface EntryCell {
+  accountId: string;
+  month: number;
+  amount: number;
+  // Leave out to keep the stored cell's provenance
+  provenance?: EntryProvenance | null;
+}
+
+export type CarryForwardMode = "january" | "all_months";
+
+export interface CarryForwardOptions {
+  mode: CarryForwardMode;
+  markEstimated: boolean;
 }
 
 export interface MonthlyTotal {
@@ -20,3 +54,33 @@ export interface MonthlyTotal {
   netWorth: number;
   hasMissingRates: boolean;
 }
+
+export type ImportRowStatus = "created" | "updated" | "skipped" | "error";
+
+// The parsed row's fields are missing when the line couldn't be parsed at all
+export type ImportRowResult<Row> = Partial<Row> & {
+  line: number;
+  status: ImportRowStatus;
+  message: string | null;
+};
+
+export interface ImportReport<Row> {
+  dryRun: boolean;
+  created: number;
+  updated: number;
+  skipped: number;
+  errored: number;
+  rows: ImportRowResult<Row>[];
+}
+
+export interface EntryImportRow {
+  accountName: string;
+  year: number;
+  month: number;
+  amount: number;
+  currency: string | null;
+}
+
+export interface EntryImportReport extends ImportReport<EntryImportRow> {
+  createdBalanceSheetYears: number[];
+}
@@ -0,0 +1,38 @@
+export interface BudgetCategory {
+  id: string;
+  name: string;
+  // Whether unspent money carries into the next month
+  rollover: boolean;
+  createdAt: string;
+}
+
+export interface Budget {
+  id: string;
+  categoryId: string;
+  year: number;
+  month: number;
+  // In the user's home currency
+  amount: number;
+  rolloverAmount: number;
+  updatedAt: string;
+}
+
+export interface BudgetLine {
+  categoryId: string;
+  categoryName: string;
+  budgeted: number;
+  rolledOver: number;
+  available: number;
+  actual: number;
+  // Negative when overspent
+  remaining: number;
+}
+
+export interface BudgetReport {
+  year: number;
+  month: number;
+  currency: string;
+  lines: BudgetLine[];
+  // Account currencies that couldn't be converted for this month
+  missingCurrencies: string[];
+}
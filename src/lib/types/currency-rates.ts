@@ -1,3 +1,5 @@
+import type { ImportReport } from "@/lib/types/balance-sheets";
+
 export interface CurrencyRate {
   id: string;
   fromCurrency: string;
@@ -8,3 +10,13 @@ export interface CurrencyRate {
   year: number;
   timestamp: string;
 }
+
+export interface RateImportRow {
+  fromCurrency: string;
+  toCurrency: string;
+  year: number;
+  month: number;
+  rate: number;
+}
+
+export type RateImportReport = ImportReport<RateImportRow>;
@@ -0,0 +1,18 @@
+export type AppErrorCode =
+  | "NOT_FOUND"
+  | "CONFLICT"
+  | "VALIDATION"
+  | "DATABASE"
+  | "NETWORK"
+  | "PROVIDER_UNSUPPORTED"
+  | "IO";
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  entity?: string;
+  id?: string | null;
+  field?: string | null;
+  provider?: string;
+  currency?: string;
+}
@@ -0,0 +1,50 @@
+// Percentages are on a 0-100 scale and null when the starting value is zero
+export interface GrowthChange {
+  absolute: number;
+  percentage: number | null;
+}
+
+export interface GrowthPoint {
+  year: number;
+  month: number;
+  netWorth: number;
+  // null when the previous month has no data
+  monthOverMonth: GrowthChange | null;
+  // null when the same month a year earlier has no data
+  rolling12Months: GrowthChange | null;
+  // Distance below the highest net worth so far
+  drawdown: GrowthChange;
+}
+
+// Year-end to year-end, using the last month with data in each year
+export interface YearlyGrowth {
+  year: number;
+  startNetWorth: number;
+  endNetWorth: number;
+  change: GrowthChange;
+}
+
+export interface MonthChange {
+  year: number;
+  month: number;
+  change: GrowthChange;
+}
+
+export interface Drawdown {
+  peakYear: number;
+  peakMonth: number;
+  troughYear: number;
+  troughMonth: number;
+  change: GrowthChange;
+}
+
+export interface GrowthReport {
+  currency: string | null;
+  points: GrowthPoint[];
+  yearOverYear: YearlyGrowth[];
+  // Compound annual growth rate between the first and last months
+  cagr: number | null;
+  bestMonth: MonthChange | null;
+  worstMonth: MonthChange | null;
+  maxDrawdown: Drawdown | null;
+}
@@ -1,3 +1,14 @@
+import type { AccountType } from "@/lib/types/accounts";
+
+export type RateQuality = "exact" | "estimated" | "missing";
+
+export interface RateWarning {
+  currency: string;
+  quality: RateQuality;
+  rateYear: number | null;
+  rateMonth: number | null;
+}
+
 export interface NetWorthDataPoint {
   year: number;
   month: number;
@@ -5,4 +16,69 @@ export interface NetWorthDataPoint {
   totalLiabilities: number;
   netWorth: number;
   currency: string;
+  rateQuality: RateQuality;
+  warnings: RateWarning[];
+  // Share (0-1) of the month's assets plus liabilities from estimated entries
+  estimatedShare: number;
+  // Active accounts with entries elsewhere in the year but none this month (no data, not zero)
+  missingAccountIds: string[];
+  // Change since the previous month split into balance and exchange rate movement;
+  // null when the previous month has no data
+  localChange: number | null;
+  currencyEffect: number | null;
+  // The same split per foreign-currency account
+  fxAttribution: FxAttribution[];
+}
+
+export interface FxAttribution {
+  accountId: string;
+  currency: string;
+  localChange: number;
+  currencyEffect: number;
+}
+
+// Liabilities are positive, as entered
+export interface NetWorthBreakdown {
+  year: number;
+  month: number;
+  currency: string;
+  bySubCategory: SubCategoryTotal[];
+  byAccount: AccountTotal[];
+  rateQuality: RateQuality;
+  warnings: RateWarning[];
+}
+
+export interface SubCategoryTotal {
+  accountType: AccountType;
+  // null for accounts without a sub-category
+  subCategory: string | null;
+  total: number;
+}
+
+export interface AccountTotal {
+  accountId: string;
+  accountType: AccountType;
+  subCategory: string | null;
+  total: number;
+}
+
+// Every filter is optional; the period is inclusive
+export interface NetWorthQuery {
+  fromYear?: number;
+  // Defaults to January
+  fromMonth?: number;
+  toYear?: number;
+  // Defaults to December
+  toMonth?: number;
+  accountIds?: string[];
+  accountType?: AccountType;
+  subCategory?: string;
+}
+
+export interface UnconvertibleMonth {
+  year: number;
+  month: number;
+  homeCurrency: string;
+  missingCurrencies: string[];
+  estimatedCurrencies: string[];
 }
@@ -0,0 +1,35 @@
+export type RecurringRuleType =
+  | "fixed_amount"
+  | "fixed_delta"
+  | "percentage_growth"
+  | "amortization";
+
+export interface RecurringRule {
+  id: string;
+  accountId: string;
+  ruleType: RecurringRuleType;
+  // fixed_amount: the balance; fixed_delta: change per month; amortization: monthly payment
+  amount: number;
+  // Annual rate as a decimal (0.05 = 5%) for percentage_growth and amortization
+  rate: number;
+  isActive: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface RecurringRuleInput {
+  accountId: string;
+  ruleType: RecurringRuleType;
+  amount: number;
+  rate: number;
+  isActive: boolean;
+}
+
+export interface DraftEntry {
+  accountId: string;
+  ruleId: string;
+  month: number;
+  amount: number;
+  // Entry already stored for this account and month
+  existingAmount: number | null;
+}
@@ -13,6 +13,8 @@ export interface RetirementPlan {
   inflationRate: number;
   createdAt: string;
   updatedAt: string;
+  // Set while the plan is in the trash
+  deletedAt: string | null;
 }
 
 export interface RetirementProjection {
@@ -0,0 +1,36 @@
+export interface Contribution {
+  id: string;
+  accountId: string;
+  year: number;
+  month: number;
+  // New money from outside the tracked accounts, in the account's currency; negative for withdrawals
+  amount: number;
+  updatedAt: string;
+}
+
+export interface MonthlyIncome {
+  id: string;
+  year: number;
+  month: number;
+  // In the user's home currency
+  amount: number;
+  updatedAt: string;
+}
+
+// A month's change in net worth split into contributions and returns
+export interface SavingsMonth {
+  year: number;
+  month: number;
+  currency: string;
+  // null when the previous month has no data
+  netWorthChange: number | null;
+  contributions: number;
+  // Part of contributions inferred from transactions
+  inferredContributions: number;
+  returns: number | null;
+  income: number | null;
+  // Share (0-100) of income; null without a positive income
+  savingsRate: number | null;
+  // Account currencies that couldn't be converted for this month
+  missingCurrencies: string[];
+}
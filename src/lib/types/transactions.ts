@@ -0,0 +1,47 @@
+export interface Transaction {
+  id: string;
+  accountId: string;
+  // YYYY-MM-DD
+  date: string;
+  // Change to the account balance (positive increases it)
+  amount: number;
+  payee: string | null;
+  category: string | null;
+  memo: string | null;
+  // The other leg of a transfer between two accounts
+  transferId: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface TransactionInput {
+  accountId: string;
+  date: string;
+  amount: number;
+  payee?: string | null;
+  category?: string | null;
+  memo?: string | null;
+}
+
+export interface TransferInput {
+  fromAccountId: string;
+  toAccountId: string;
+  date: string;
+  // Always positive
+  amount: number;
+  // What arrives, in the destination account's currency; required across currencies
+  toAmount?: number | null;
+  memo?: string | null;
+}
+
+export interface ReconciliationDiscrepancy {
+  accountId: string;
+  year: number;
+  month: number;
+  openingBalance: number;
+  closingBalance: number;
+  snapshotChange: number;
+  transactionsTotal: number;
+  // snapshotChange - transactionsTotal
+  difference: number;
+}
@@ -0,0 +1,10 @@
+import type { Account } from "@/lib/types/accounts";
+import type { BalanceSheet } from "@/lib/types/balance-sheets";
+import type { RetirementPlan } from "@/lib/types/retirement";
+
+// Deleted but not yet purged, most recently deleted first
+export interface Trash {
+  accounts: Account[];
+  balanceSheets: BalanceSheet[];
+  retirementPlans: RetirementPlan[];
+}
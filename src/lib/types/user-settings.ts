@@ -8,6 +8,7 @@ export interface UserSettings {
   homeCurrency: string;
   theme: Theme;
   needsExchangeSync: boolean;
+  backupRetention: number;
   createdAt: string;
   updatedAt: string;
 }